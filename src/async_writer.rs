@@ -0,0 +1,220 @@
+use super::{Accepts, Channels, Dynamic, Encoder, EncoderInput, EncoderOutput, EncoderFlush, EncodeError, FlushNoGap};
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use core::pin::Pin;
+use core::task::{Context, Poll};
+use std::io::{self, SeekFrom};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+use tokio::io::{AsyncWrite, AsyncWriteExt, AsyncSeek, AsyncSeekExt};
+
+///PCM chunk, that can be encoded by [EncoderStream]
+///
+///Implemented for every [EncoderInput] that is `Copy` (i.e. [MonoPcm](crate::MonoPcm), [DualPcm](crate::DualPcm) and [InterleavedPcm](crate::InterleavedPcm)).
+///
+///`C` is number of channels of encoder (see [Channels]), so that typed encoder accepts only matching input.
+///
+///Owned PCM data can implement it by delegating to [Encoder::encode_into]:
+///
+///```rust
+///use mp3lame_encoder::{Encoder, EncoderOutput, EncodeError, MonoPcm, PcmChunk};
+///
+///struct Chunk(Vec<f32>);
+///
+///impl PcmChunk for Chunk {
+///    fn encode_into<O: EncoderOutput>(&self, encoder: &mut Encoder, output: &mut O) -> Result<usize, EncodeError> {
+///        encoder.encode_into(MonoPcm(&self.0[..]), output)
+///    }
+///}
+///```
+pub trait PcmChunk<C: Channels = Dynamic> {
+    ///Encodes `self` using provided encoder, reserving enough space within `output`
+    fn encode_into<O: EncoderOutput>(&self, encoder: &mut Encoder<C>, output: &mut O) -> Result<usize, EncodeError>;
+}
+
+impl<C: Accepts<I>, I: EncoderInput + Copy> PcmChunk<C> for I {
+    #[inline(always)]
+    fn encode_into<O: EncoderOutput>(&self, encoder: &mut Encoder<C>, output: &mut O) -> Result<usize, EncodeError> {
+        encoder.encode_into(*self, output)
+    }
+}
+
+///Asynchronous MP3 writer, streaming encoded data into `W`
+///
+///Available with `tokio` feature.
+///
+///Unlike [Mp3Writer](crate::Mp3Writer), it doesn't require `W` to be seekable.
+///But in such case LAME tag cannot be written, hence you should disable it via [Builder::set_to_write_vbr_tag](crate::Builder::set_to_write_vbr_tag).
+///If `W` is seekable, you can use [AsyncMp3Writer::finish_with_lame_tag].
+///
+///Note that encoding itself is performed within the calling task.
+pub struct AsyncMp3Writer<W, C = Dynamic> {
+    encoder: Encoder<C>,
+    writer: W,
+    buffer: Vec<u8>,
+    written: u64,
+}
+
+impl<W: AsyncWrite + Unpin, C: Channels> AsyncMp3Writer<W, C> {
+    ///Creates new instance
+    pub fn new(encoder: Encoder<C>, writer: W) -> Self {
+        Self {
+            encoder,
+            writer,
+            buffer: Vec::new(),
+            written: 0,
+        }
+    }
+
+    #[inline(always)]
+    ///Access underlying encoder
+    pub fn encoder(&self) -> &Encoder<C> {
+        &self.encoder
+    }
+
+    #[inline(always)]
+    ///Access underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    #[inline(always)]
+    ///Returns number of bytes written so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    async fn write_buffer(&mut self) -> io::Result<usize> {
+        self.writer.write_all(&self.buffer).await?;
+        let written = self.buffer.len();
+        self.written = self.written.saturating_add(written as u64);
+        self.buffer.clear();
+        Ok(written)
+    }
+
+    ///Encodes PCM data, writing whatever available onto underlying writer
+    ///
+    ///Returns number of bytes written.
+    pub async fn encode<I: EncoderInput>(&mut self, input: I) -> io::Result<usize> where C: Accepts<I> {
+        if let Err(error) = self.encoder.encode_into(input, &mut self.buffer) {
+            return Err(io::Error::new(io::ErrorKind::Other, error));
+        }
+        self.write_buffer().await
+    }
+
+    async fn flush_encoder<T: EncoderFlush>(&mut self) -> io::Result<()> {
+        if let Err(error) = self.encoder.flush_into::<T>(&mut self.buffer) {
+            return Err(io::Error::new(io::ErrorKind::Other, error));
+        }
+        self.write_buffer().await?;
+        Ok(())
+    }
+
+    ///Flushes encoder without writing LAME tag
+    ///
+    ///Refer to [Encoder::flush] for flush types
+    ///
+    ///Returns underlying writer.
+    pub async fn finish<T: EncoderFlush>(mut self) -> io::Result<W> {
+        self.flush_encoder::<T>().await?;
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: AsyncWrite + AsyncSeek + Unpin, C: Channels> AsyncMp3Writer<W, C> {
+    ///Flushes encoder, writing LAME tag (if any) after [Id3Tag](crate::Id3Tag)
+    ///
+    ///Writer position must not be changed while encoding, as start of MP3 data is determined using number of bytes written.
+    ///
+    ///Refer to [Encoder::flush] for flush types
+    ///
+    ///Returns underlying writer positioned at the end of MP3 data.
+    pub async fn finish_with_lame_tag<T: EncoderFlush>(mut self) -> io::Result<W> {
+        self.flush_encoder::<T>().await?;
+
+        let lame_tag_size = self.encoder.lame_tag_size();
+        if lame_tag_size > 0 {
+            self.buffer.reserve(lame_tag_size);
+            if self.encoder.lame_tag_encode_to_vec(&mut self.buffer).is_some() {
+                let end = self.writer.stream_position().await?;
+                //MP3 data is written continuously, so its start can be determined from current position
+                let start = end.saturating_sub(self.written);
+                let lame_tag_pos = start.saturating_add(self.encoder.id3v2_tag_size() as u64);
+
+                self.writer.seek(SeekFrom::Start(lame_tag_pos)).await?;
+                self.writer.write_all(&self.buffer).await?;
+                self.writer.seek(SeekFrom::Start(end)).await?;
+            }
+        }
+
+        self.writer.flush().await?;
+        Ok(self.writer)
+    }
+}
+
+///Stream of MP3 data, produced by encoding stream of PCM chunks
+///
+///Available with `tokio` feature.
+///
+///Once underlying stream is over, encoder is flushed using `T` (see [Encoder::flush])
+///
+///LAME tag cannot be written into stream, so you should disable it via [Builder::set_to_write_vbr_tag](crate::Builder::set_to_write_vbr_tag).
+pub struct EncoderStream<S, T = FlushNoGap, C = Dynamic> {
+    encoder: Encoder<C>,
+    stream: S,
+    buffer: BytesMut,
+    is_finished: bool,
+    _flush: PhantomData<fn() -> T>,
+}
+
+impl<S: Stream + Unpin, T: EncoderFlush, C: Channels> EncoderStream<S, T, C> where S::Item: PcmChunk<C> {
+    ///Creates new instance
+    pub fn new(encoder: Encoder<C>, stream: S) -> Self {
+        Self {
+            encoder,
+            stream,
+            buffer: BytesMut::new(),
+            is_finished: false,
+            _flush: PhantomData,
+        }
+    }
+
+    #[inline(always)]
+    ///Access underlying encoder
+    pub fn encoder(&self) -> &Encoder<C> {
+        &self.encoder
+    }
+}
+
+impl<S: Stream + Unpin, T: EncoderFlush, C: Channels + Unpin> Stream for EncoderStream<S, T, C> where S::Item: PcmChunk<C> {
+    type Item = Result<Bytes, EncodeError>;
+
+    fn poll_next(self: Pin<&mut Self>, ctx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        while !this.is_finished {
+            let result = match Pin::new(&mut this.stream).poll_next(ctx) {
+                Poll::Ready(Some(chunk)) => chunk.encode_into(&mut this.encoder, &mut this.buffer),
+                Poll::Ready(None) => {
+                    this.is_finished = true;
+                    this.encoder.flush_into::<T>(&mut this.buffer)
+                },
+                Poll::Pending => return Poll::Pending,
+            };
+
+            match result {
+                Ok(0) => continue,
+                Ok(_) => return Poll::Ready(Some(Ok(this.buffer.split().freeze()))),
+                Err(error) => {
+                    this.is_finished = true;
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+
+        Poll::Ready(None)
+    }
+}
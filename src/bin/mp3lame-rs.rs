@@ -0,0 +1,274 @@
+//! `lame` compatible command line encoder.
+//!
+//! Encodes WAV or raw PCM into MP3, supporting subset of `lame` options.
+
+use mp3lame_encoder::{encode_wav_data, Bitrate, Builder, FlushGap, FlushNoGap, Id3Tag, Id3TagError, Mode, Mp3Writer, Quality, VbrMode, WavHeader, WavSampleFormat, MAX_ALBUM_ART_SIZE};
+
+use std::{env, fs, io, process};
+use std::io::Read;
+use std::num::NonZeroU32;
+use std::path::Path;
+
+const USAGE: &str = "Usage: mp3lame-rs [options] <input> [output]
+
+Input is WAV file, or raw PCM if -r is specified. Use '-' to read from stdin.
+If output is not specified, it is input with .mp3 extension.
+
+Options:
+    -b <bitrate>      Bitrate in kbps (CBR), or minimum bitrate when used with -V/--abr
+    -V <0..9>         VBR quality, 0 is best
+    --abr <bitrate>   ABR with specified average bitrate in kbps
+    -q <0..9>         Algorithm quality, 0 is best
+    -m <s|j|m>        Mode: stereo, joint stereo or mono
+    --resample <kHz>  Output sample rate
+    -r                Input is raw PCM (little endian, unsigned if 8-bit, otherwise signed)
+    -s <kHz>          Sample rate of raw PCM (default 44.1)
+    --bitwidth <n>    Bit width of raw PCM: 8, 16, 24 or 32 (default 16)
+    --tt <title>      ID3 title
+    --ta <artist>     ID3 artist
+    --tl <album>      ID3 album
+    --ty <year>       ID3 year
+    --tc <comment>    ID3 comment
+    --ti <file>       ID3 album art (PNG, JPG or GIF)
+    --nogap           Flush without padding end of stream with silence
+    -h, --help        Print this message";
+
+#[derive(Default)]
+struct Args {
+    input: String,
+    output: Option<String>,
+    bitrate: Option<Bitrate>,
+    vbr_quality: Option<Quality>,
+    abr: Option<Bitrate>,
+    quality: Option<Quality>,
+    mode: Option<Mode>,
+    resample: Option<NonZeroU32>,
+    raw: bool,
+    raw_sample_rate: Option<u32>,
+    raw_bitwidth: Option<u16>,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    year: Option<String>,
+    comment: Option<String>,
+    album_art: Option<String>,
+    nogap: bool,
+}
+
+fn parse_bitrate(value: &str) -> Result<Bitrate, String> {
+    let bitrate = match value.parse::<u16>() {
+        Ok(8) => Bitrate::Kbps8,
+        Ok(16) => Bitrate::Kbps16,
+        Ok(24) => Bitrate::Kbps24,
+        Ok(32) => Bitrate::Kbps32,
+        Ok(40) => Bitrate::Kbps40,
+        Ok(48) => Bitrate::Kbps48,
+        Ok(56) => Bitrate::Kbps56,
+        Ok(64) => Bitrate::Kbps64,
+        Ok(80) => Bitrate::Kbps80,
+        Ok(96) => Bitrate::Kbps96,
+        Ok(112) => Bitrate::Kbps112,
+        Ok(128) => Bitrate::Kbps128,
+        Ok(144) => Bitrate::Kbps144,
+        Ok(160) => Bitrate::Kbps160,
+        Ok(192) => Bitrate::Kbps192,
+        Ok(224) => Bitrate::Kbps224,
+        Ok(256) => Bitrate::Kbps256,
+        Ok(320) => Bitrate::Kbps320,
+        _ => return Err(format!("invalid bitrate '{value}'")),
+    };
+    Ok(bitrate)
+}
+
+fn parse_quality(value: &str) -> Result<Quality, String> {
+    let quality = match value.parse::<u8>() {
+        Ok(0) => Quality::Best,
+        Ok(1) => Quality::SecondBest,
+        Ok(2) => Quality::NearBest,
+        Ok(3) => Quality::VeryNice,
+        Ok(4) => Quality::Nice,
+        Ok(5) => Quality::Good,
+        Ok(6) => Quality::Decent,
+        Ok(7) => Quality::Ok,
+        Ok(8) => Quality::SecondWorst,
+        Ok(9) => Quality::Worst,
+        _ => return Err(format!("invalid quality '{value}'")),
+    };
+    Ok(quality)
+}
+
+//Accepts both kHz (as lame does) and Hz
+fn parse_sample_rate(value: &str) -> Result<u32, String> {
+    match value.parse::<f64>() {
+        Ok(rate) if rate > 0.0 && rate < 1000.0 => Ok((rate * 1000.0).round() as u32),
+        Ok(rate) if rate >= 1000.0 && rate <= u32::MAX as f64 => Ok(rate.round() as u32),
+        _ => Err(format!("invalid sample rate '{value}'")),
+    }
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Option<Args>, String> {
+    let mut result = Args::default();
+    let mut positional = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let mut value = || args.next().ok_or_else(|| format!("missing value for '{arg}'"));
+
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "-b" => result.bitrate = Some(parse_bitrate(&value()?)?),
+            "-V" => result.vbr_quality = Some(parse_quality(&value()?)?),
+            "--abr" => result.abr = Some(parse_bitrate(&value()?)?),
+            "-q" => result.quality = Some(parse_quality(&value()?)?),
+            "-m" => result.mode = Some(match value()?.as_str() {
+                "s" => Mode::Stereo,
+                "j" => Mode::JointStereo,
+                "m" => Mode::Mono,
+                mode @ ("d" | "f") => return Err(format!("unsupported mode '{mode}'")),
+                mode => return Err(format!("invalid mode '{mode}'")),
+            }),
+            "--resample" => result.resample = NonZeroU32::new(parse_sample_rate(&value()?)?),
+            "-r" => result.raw = true,
+            "-s" => result.raw_sample_rate = Some(parse_sample_rate(&value()?)?),
+            "--bitwidth" => result.raw_bitwidth = Some(match value()?.parse::<u16>() {
+                Ok(bitwidth @ (8 | 16 | 24 | 32)) => bitwidth,
+                _ => return Err("invalid bit width".to_owned()),
+            }),
+            "--tt" => result.title = Some(value()?),
+            "--ta" => result.artist = Some(value()?),
+            "--tl" => result.album = Some(value()?),
+            "--ty" => result.year = Some(value()?),
+            "--tc" => result.comment = Some(value()?),
+            "--ti" => result.album_art = Some(value()?),
+            "--nogap" => result.nogap = true,
+            "-" => positional.push(arg),
+            option if option.starts_with('-') => return Err(format!("unknown option '{option}'")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let mut positional = positional.into_iter();
+    result.input = positional.next().ok_or("missing input")?;
+    result.output = positional.next();
+    if positional.next().is_some() {
+        return Err("too many arguments".to_owned());
+    }
+
+    Ok(Some(result))
+}
+
+fn configure(args: &Args, builder: &mut Builder, album_art: &[u8]) -> Result<(), String> {
+    //Bitrates are validated against output sample rate, so it must be set first
+    if let Some(rate) = args.resample {
+        builder.set_output_sample_rate(Some(rate)).map_err(|error| format!("--resample: {error}"))?;
+    }
+
+    if let Some(quality) = args.vbr_quality {
+        builder.set_vbr_mode(VbrMode::Mtrh).map_err(|error| format!("-V: {error}"))?;
+        builder.set_vbr_quality(quality).map_err(|error| format!("-V: {error}"))?;
+    } else if let Some(bitrate) = args.abr {
+        builder.set_vbr_mode(VbrMode::Abr).map_err(|error| format!("--abr: {error}"))?;
+        builder.set_vbr_mean_bitrate(bitrate).map_err(|error| format!("--abr: {error}"))?;
+    }
+
+    if let Some(bitrate) = args.bitrate {
+        if args.vbr_quality.is_some() || args.abr.is_some() {
+            builder.set_vbr_min_bitrate(bitrate).map_err(|error| format!("-b: {error}"))?;
+        } else {
+            builder.set_brate(bitrate).map_err(|error| format!("-b: {error}"))?;
+        }
+    }
+    if let Some(quality) = args.quality {
+        builder.set_quality(quality).map_err(|error| format!("-q: {error}"))?;
+    }
+    if let Some(mode) = args.mode {
+        builder.set_mode(mode).map_err(|error| format!("-m: {error}"))?;
+    }
+    fn text(value: &Option<String>) -> &[u8] {
+        value.as_deref().unwrap_or_default().as_bytes()
+    }
+
+    builder.set_id3_tag(Id3Tag {
+        title: text(&args.title),
+        artist: text(&args.artist),
+        album: text(&args.album),
+        album_art,
+        year: text(&args.year),
+        comment: text(&args.comment),
+    }).map_err(|error| match error {
+        Id3TagError::AlbumArtOverflow => format!("--ti: album art exceeds {MAX_ALBUM_ART_SIZE} bytes"),
+        error => format!("unable to set ID3 tag: {error}"),
+    })
+}
+
+fn run(args: Args) -> Result<(), String> {
+    let output = match args.output.as_deref() {
+        Some("-") => return Err("output must be seekable file in order to write LAME tag".to_owned()),
+        Some(output) => output.to_owned(),
+        None if args.input == "-" => return Err("output must be specified when reading from stdin".to_owned()),
+        None => Path::new(&args.input).with_extension("mp3").to_string_lossy().into_owned(),
+    };
+
+    let mut input: Box<dyn Read> = match args.input.as_str() {
+        "-" => Box::new(io::stdin().lock()),
+        path => Box::new(io::BufReader::new(fs::File::open(path).map_err(|error| format!("{path}: {error}"))?)),
+    };
+
+    let header = if args.raw {
+        WavHeader {
+            sample_rate: args.raw_sample_rate.unwrap_or(44_100),
+            num_channels: match args.mode {
+                Some(Mode::Mono) => 1,
+                _ => 2,
+            },
+            sample_format: match args.raw_bitwidth.unwrap_or(16) {
+                8 => WavSampleFormat::U8,
+                24 => WavSampleFormat::I24,
+                32 => WavSampleFormat::I32,
+                _ => WavSampleFormat::I16,
+            },
+            data_len: None,
+        }
+    } else {
+        WavHeader::read(&mut input).map_err(|error| format!("{}: {error}", args.input))?
+    };
+
+    let album_art = match args.album_art.as_deref() {
+        Some(path) => fs::read(path).map_err(|error| format!("{path}: {error}"))?,
+        None => Vec::new(),
+    };
+
+    let mut builder = Builder::new().ok_or("unable to create LAME encoder")?;
+    header.configure(&mut builder).map_err(|error| format!("{}: {error}", args.input))?;
+    configure(&args, &mut builder, &album_art)?;
+    let encoder = builder.build().map_err(|error| format!("invalid encoder parameters: {error}"))?;
+
+    let file = fs::File::create(&output).map_err(|error| format!("{output}: {error}"))?;
+    let mut writer = Mp3Writer::new(encoder, io::BufWriter::new(file)).map_err(|error| format!("{output}: {error}"))?;
+    encode_wav_data(&header, input, &mut writer).map_err(|error| format!("{output}: {error}"))?;
+    let result = match args.nogap {
+        true => writer.finish::<FlushNoGap>(),
+        false => writer.finish::<FlushGap>(),
+    };
+    result.map_err(|error| format!("{output}: {error}"))?;
+
+    Ok(())
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(Some(args)) => args,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        },
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            process::exit(1);
+        }
+    };
+
+    if let Err(error) = run(args) {
+        eprintln!("{error}");
+        process::exit(1);
+    }
+}
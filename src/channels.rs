@@ -0,0 +1,54 @@
+use super::{EncoderInput, MonoPcm, MonoPcm24, DualPcm, DualPcm24, InterleavedPcm, InterleavedPcm24};
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+///Number of channels of [Encoder](crate::Encoder), known at compile time.
+///
+///Use [Builder::build_typed](crate::Builder::build_typed) to create encoder with specific channels.
+pub trait Channels: sealed::Sealed {
+    ///Number of channels, if it is fixed.
+    const NUM_CHANNELS: Option<u8>;
+}
+
+///Encoder, that accepts input with `I` shape.
+pub trait Accepts<I: EncoderInput>: Channels {
+}
+
+///Single channel encoder, that only accepts mono input.
+pub enum Mono {}
+
+///Two channels encoder, that only accepts dual or interleaved input.
+pub enum Stereo {}
+
+///Encoder with number of channels known only at runtime.
+///
+///It accepts any input, which is validated before encoding (see [EncoderInput::validate]).
+pub enum Dynamic {}
+
+impl sealed::Sealed for Mono {}
+impl sealed::Sealed for Stereo {}
+impl sealed::Sealed for Dynamic {}
+
+impl Channels for Mono {
+    const NUM_CHANNELS: Option<u8> = Some(1);
+}
+
+impl Channels for Stereo {
+    const NUM_CHANNELS: Option<u8> = Some(2);
+}
+
+impl Channels for Dynamic {
+    const NUM_CHANNELS: Option<u8> = None;
+}
+
+impl<I: EncoderInput> Accepts<I> for Dynamic {}
+
+impl<'a, T> Accepts<MonoPcm<'a, T>> for Mono where MonoPcm<'a, T>: EncoderInput {}
+impl Accepts<MonoPcm24<'_>> for Mono {}
+
+impl<'a, T> Accepts<DualPcm<'a, T>> for Stereo where DualPcm<'a, T>: EncoderInput {}
+impl<'a, T> Accepts<InterleavedPcm<'a, T>> for Stereo where InterleavedPcm<'a, T>: EncoderInput {}
+impl Accepts<DualPcm24<'_>> for Stereo {}
+impl Accepts<InterleavedPcm24<'_>> for Stereo {}
@@ -0,0 +1,110 @@
+use super::{ffi, Bitrate, Channels, Encoder, Mode, MpegVersion, Quality, VbrMode};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+///Effective parameters of [Encoder], as chosen by LAME during initialization.
+///
+///Retrieved via [Encoder::config]
+pub struct EncoderConfig {
+    ///Input sample rate
+    pub sample_rate: u32,
+    ///Output sample rate
+    pub output_sample_rate: u32,
+    ///Number of input channels
+    pub num_channels: u8,
+    ///MPEG version of output
+    pub version: MpegVersion,
+    ///MPEG mode
+    pub mode: Mode,
+    ///VBR mode
+    pub vbr_mode: VbrMode,
+    ///Constant bitrate.
+    ///
+    ///`None` if VBR is used or bitrate is not standard (i.e. free format).
+    pub bitrate: Option<Bitrate>,
+    ///ABR target bitrate (as kbps), if ABR is used.
+    pub vbr_mean_bitrate: Option<u16>,
+    ///Minimum VBR bitrate (as kbps), if VBR is used.
+    pub vbr_min_bitrate: Option<u16>,
+    ///Maximum VBR bitrate (as kbps), if VBR is used.
+    pub vbr_max_bitrate: Option<u16>,
+    ///VBR quality, if VBR other than ABR is used.
+    pub vbr_quality: Option<Quality>,
+    ///Algorithm quality
+    pub quality: Quality,
+    ///Lowpass filter frequency, if enabled
+    pub lowpass_freq: Option<u32>,
+    ///Highpass filter frequency, if enabled
+    pub highpass_freq: Option<u32>,
+    ///Number of samples per channel in a frame
+    pub frame_size: usize,
+    ///Number of samples, added by encoder at the start of stream
+    pub encoder_delay: usize,
+    ///Indicates whether free format bitrate is used
+    pub free_format: bool,
+    ///Indicates whether strict ISO compliance is enforced
+    pub strict_iso: bool,
+    ///Indicates whether bit reservoir is disabled
+    pub disable_reservoir: bool,
+}
+
+impl<C: Channels> Encoder<C> {
+    ///Returns effective parameters of encoder
+    pub fn config(&self) -> EncoderConfig {
+        let ptr = self.ptr();
+        let (version, mode, vbr, brate, mean, min, max, vbr_q, quality, frame_size, encoder_delay) = unsafe {
+            (
+                ffi::lame_get_version(ptr),
+                ffi::lame_get_mode(ptr),
+                ffi::lame_get_VBR(ptr),
+                ffi::lame_get_brate(ptr),
+                ffi::lame_get_VBR_mean_bitrate_kbps(ptr),
+                ffi::lame_get_VBR_min_bitrate_kbps(ptr),
+                ffi::lame_get_VBR_max_bitrate_kbps(ptr),
+                ffi::lame_get_VBR_q(ptr),
+                ffi::lame_get_quality(ptr),
+                ffi::lame_get_framesize(ptr),
+                ffi::lame_get_encoder_delay(ptr),
+            )
+        };
+        let output_sample_rate = unsafe {
+            ffi::lame_get_out_samplerate(ptr) as u32
+        };
+
+        //Positive kbps, fitting into u16
+        let kbps = |kbps: core::ffi::c_int| u16::try_from(kbps).ok().filter(|kbps| *kbps > 0);
+        let vbr_mode = VbrMode::from_raw(vbr);
+        let (bitrate, vbr_mean_bitrate, vbr_min_bitrate, vbr_max_bitrate, vbr_quality) = match vbr_mode {
+            VbrMode::Off => (kbps(brate).and_then(Bitrate::from_kbps), None, None, None, None),
+            VbrMode::Abr => (None, kbps(mean), kbps(min), kbps(max), None),
+            _ => (None, None, kbps(min), kbps(max), Some(Quality::from_raw(vbr_q))),
+        };
+
+        EncoderConfig {
+            sample_rate: self.sample_rate(),
+            output_sample_rate,
+            num_channels: self.num_channels(),
+            //LAME uses 0 for MPEG-2, 1 for MPEG-1 and 2 for MPEG-2.5
+            version: match version {
+                0 => MpegVersion::Mpeg2,
+                2 => MpegVersion::Mpeg25,
+                _ => MpegVersion::Mpeg1,
+            },
+            mode: Mode::from_raw(mode as _),
+            vbr_mode,
+            bitrate,
+            vbr_mean_bitrate,
+            vbr_min_bitrate,
+            vbr_max_bitrate,
+            vbr_quality,
+            quality: Quality::from_raw(quality),
+            lowpass_freq: self.lowpass_freq(),
+            highpass_freq: self.highpass_freq(),
+            frame_size: frame_size.max(0) as usize,
+            encoder_delay: encoder_delay.max(0) as usize,
+            free_format: self.is_free_format(),
+            strict_iso: self.is_strict_iso(),
+            disable_reservoir: self.is_reservoir_disabled(),
+        }
+    }
+}
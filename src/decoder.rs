@@ -0,0 +1,156 @@
+use super::{ffi, Mode};
+
+use core::{fmt, mem};
+use core::ptr::NonNull;
+
+///Maximum number of samples per channel that can be produced out of single MP3 frame.
+///
+///Output buffers passed to [Decoder::decode] must be at least of this size.
+pub const MAX_FRAME_SAMPLES: usize = 1152;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Decoder errors
+pub enum DecodeError {
+    ///Indicates output buffer is insufficient.
+    ///
+    ///Each channel buffer must be at least [MAX_FRAME_SAMPLES]
+    BufferTooSmall,
+    ///Unable to decode input data.
+    InvalidData,
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+}
+
+impl fmt::Display for DecodeError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::BufferTooSmall => fmt.write_str("output buffer is insufficient for decoded frame"),
+            Self::InvalidData => fmt.write_str("unable to decode input data"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Information about decoded MP3 stream
+pub struct Mp3Info {
+    ///Sample rate
+    pub sample_rate: u32,
+    ///Number of channels
+    pub num_channels: u8,
+    ///Bitrate of last decoded frame (as kbps)
+    pub bitrate: u16,
+    ///MPEG mode
+    pub mode: Mode,
+    ///Number of samples per frame
+    pub frame_size: u16,
+    ///Total number of frames in stream, if known (i.e. stream has LAME tag)
+    pub total_frames: Option<u32>,
+    ///Number of decoded frames so far
+    pub frame_num: u32,
+}
+
+impl Mp3Info {
+    fn from_raw(data: &ffi::mp3data_struct) -> Option<Self> {
+        if data.header_parsed != 1 {
+            return None;
+        }
+
+        Some(Self {
+            sample_rate: data.samplerate as _,
+            num_channels: data.stereo as _,
+            bitrate: data.bitrate as _,
+            mode: Mode::from_raw(data.mode as _),
+            frame_size: data.framesize as _,
+            total_frames: match data.totalframes {
+                0 => None,
+                total_frames => Some(total_frames as _),
+            },
+            frame_num: data.framenum as _,
+        })
+    }
+}
+
+///MP3 decoder, based on LAME's bundled mpglib.
+///
+///Available with `decoder` feature.
+///
+///## Usage
+///
+///Decoder buffers input internally and produces at most one frame per call.
+///Therefore once you feed input chunk, you should call [Decoder::decode] with empty input
+///until it returns 0 in order to retrieve all buffered frames.
+pub struct Decoder {
+    inner: NonNull<ffi::hip_global_flags>,
+    data: ffi::mp3data_struct,
+}
+
+impl Decoder {
+    #[inline]
+    ///Creates new decoder
+    ///
+    ///Returns `None` if unable to allocate struct.
+    pub fn new() -> Option<Self> {
+        let ptr = unsafe {
+            ffi::hip_decode_init()
+        };
+
+        NonNull::new(ptr).map(|inner| Self {
+            inner,
+            data: unsafe {
+                mem::zeroed()
+            },
+        })
+    }
+
+    #[inline(always)]
+    fn ptr(&self) -> ffi::hip_t {
+        self.inner.as_ptr()
+    }
+
+    #[inline]
+    ///Returns information about MP3 stream, if any frame header has been parsed.
+    pub fn info(&self) -> Option<Mp3Info> {
+        Mp3Info::from_raw(&self.data)
+    }
+
+    ///Attempts to decode MP3 data, writing single frame of PCM data into `left` and `right`
+    ///
+    ///### Arguments:
+    ///
+    /// - `input` - MP3 data. Can be empty to retrieve next frame from internal buffer.
+    /// - `left` - Left channel output. Must be at least [MAX_FRAME_SAMPLES].
+    /// - `right` - Right channel output. Must be at least [MAX_FRAME_SAMPLES]. Not written for mono stream.
+    ///
+    ///### Result:
+    ///On success, returns number of samples written per channel.
+    ///0 indicates that more input is required.
+    pub fn decode(&mut self, input: &[u8], left: &mut [i16], right: &mut [i16]) -> Result<usize, DecodeError> {
+        if left.len() < MAX_FRAME_SAMPLES || right.len() < MAX_FRAME_SAMPLES {
+            return Err(DecodeError::BufferTooSmall);
+        }
+
+        //hip_decode1_headers() signature takes mutable pointer, but it never modifies input
+        let result = unsafe {
+            ffi::hip_decode1_headers(self.ptr(), input.as_ptr() as _, input.len(), left.as_mut_ptr(), right.as_mut_ptr(), &mut self.data)
+        };
+
+        match result {
+            result if result >= 0 => Ok(result as usize),
+            _ => Err(DecodeError::InvalidData),
+        }
+    }
+}
+
+impl Drop for Decoder {
+    #[inline]
+    fn drop(&mut self) {
+        unsafe {
+            ffi::hip_decode_exit(self.ptr());
+        }
+    }
+}
+
+unsafe impl Send for Decoder {}
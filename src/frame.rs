@@ -0,0 +1,314 @@
+use super::{Channels, Encoder, EncoderOutput, Mode, MpegVersion, ID3V1_TAG_SIZE};
+
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+const FRAME_HEADER_SIZE: usize = 4;
+const ID3V2_HEADER_SIZE: usize = 10;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///MPEG Layer III frame header
+pub struct FrameHeader {
+    ///MPEG version
+    pub version: MpegVersion,
+    ///Bitrate (as kbps)
+    pub bitrate: u16,
+    ///Sample rate
+    pub sample_rate: u32,
+    ///Indicates whether frame is padded with extra byte
+    pub padding: bool,
+    ///Indicates whether frame is protected by CRC
+    pub crc: bool,
+    ///Channel mode
+    pub mode: Mode,
+}
+
+impl FrameHeader {
+    ///Parses Layer III frame header from start of `data`
+    ///
+    ///Returns `None` if `data` doesn't start with valid header.
+    ///Free format frames are not supported as their size cannot be determined from header.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < FRAME_HEADER_SIZE || data[0] != 0xFF || (data[1] & 0xE0) != 0xE0 {
+            return None;
+        }
+
+        let version = match (data[1] >> 3) & 0b11 {
+            0b00 => MpegVersion::Mpeg25,
+            0b10 => MpegVersion::Mpeg2,
+            0b11 => MpegVersion::Mpeg1,
+            _ => return None,
+        };
+        //Only layer III
+        if (data[1] >> 1) & 0b11 != 0b01 {
+            return None;
+        }
+
+        //Index 0 is free format
+        let bitrate = match (data[2] >> 4) as usize {
+            0 | 15 => return None,
+            idx => version.bitrates()[idx - 1],
+        };
+
+        let sample_rate = match (data[2] >> 2) & 0b11 {
+            3 => return None,
+            idx => {
+                let sample_rate = [44_100, 48_000, 32_000][idx as usize];
+                match version {
+                    MpegVersion::Mpeg1 => sample_rate,
+                    MpegVersion::Mpeg2 => sample_rate / 2,
+                    MpegVersion::Mpeg25 => sample_rate / 4,
+                }
+            }
+        };
+
+        Some(Self {
+            version,
+            bitrate,
+            sample_rate,
+            padding: (data[2] >> 1) & 1 == 1,
+            crc: data[1] & 1 == 0,
+            mode: match data[3] >> 6 {
+                0b00 => Mode::Stereo,
+                0b01 => Mode::JointStereo,
+                0b10 => Mode::DaulChannel,
+                _ => Mode::Mono,
+            },
+        })
+    }
+
+    #[inline]
+    ///Returns number of samples per channel in the frame
+    pub const fn samples_num(&self) -> usize {
+        match self.version {
+            MpegVersion::Mpeg1 => 1152,
+            MpegVersion::Mpeg2 | MpegVersion::Mpeg25 => 576,
+        }
+    }
+
+    #[inline]
+    ///Returns size of the frame in bytes, including header
+    pub const fn frame_len(&self) -> usize {
+        let bytes_per_sample = self.samples_num() / 8;
+        let len = bytes_per_sample * self.bitrate as usize * 1000 / self.sample_rate as usize;
+        if self.padding {
+            len + 1
+        } else {
+            len
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+///Single MP3 frame
+pub struct Mp3Frame<'a> {
+    ///Frame header
+    pub header: FrameHeader,
+    ///Frame data, including header
+    pub data: &'a [u8],
+    ///Position of the first sample of the frame within the stream.
+    ///
+    ///Note that it includes encoder delay.
+    pub sample_position: u64,
+    ///Position of the first byte of the frame within the stream, including ID3 tag and LAME tag.
+    pub byte_position: u64,
+}
+
+impl Mp3Frame<'_> {
+    #[inline]
+    ///Returns offset (in bytes) of frame's main data, located within previous frames due to bit reservoir.
+    ///
+    ///Returns `None` if `data` is too short to contain side info.
+    pub fn main_data_begin(&self) -> Option<u16> {
+        //Side info follows header and CRC
+        let side_info = match self.header.crc {
+            true => self.data.get(FRAME_HEADER_SIZE + 2..)?,
+            false => self.data.get(FRAME_HEADER_SIZE..)?,
+        };
+        match self.header.version {
+            MpegVersion::Mpeg1 => match side_info {
+                [first, second, ..] => Some(((*first as u16) << 1) | (*second >> 7) as u16),
+                _ => None,
+            },
+            MpegVersion::Mpeg2 | MpegVersion::Mpeg25 => side_info.first().map(|first| *first as u16),
+        }
+    }
+
+    #[inline]
+    ///Returns whether frame can be decoded without preceding frames.
+    ///
+    ///Stream can be cut or spliced at [byte_position](Self::byte_position) of such frame.
+    ///
+    ///Only disabling bit reservoir via [Builder::set_disable_reservoir](crate::Builder::set_disable_reservoir) guarantees that every frame is a splice point.
+    ///LAME cannot limit it to particular frames (e.g. every Nth frame), so otherwise splice points occur only where reservoir happens to be empty.
+    pub fn is_splice_point(&self) -> bool {
+        self.main_data_begin() == Some(0)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+///Part of MP3 stream
+pub enum Mp3Chunk<'a> {
+    ///ID3v2 tag at the start of stream or ID3v1 tag at the end of stream
+    Id3Tag(&'a [u8]),
+    ///Frame reserved for LAME tag.
+    ///
+    ///It should be replaced with actual tag, once encoding is finished (see [Encoder::lame_tag_encode])
+    LameTag(&'a [u8]),
+    ///Audio frame
+    Frame(Mp3Frame<'a>),
+    ///Data that cannot be recognized, skipped until next frame
+    Unknown(&'a [u8]),
+}
+
+///Splits encoder output into individual frames.
+///
+///Can be used as output of [Encoder::encode_into] and [Encoder::flush_into]
+///after which complete frames can be retrieved via [FrameSplitter::next_chunk].
+///
+///## Example
+///
+///```rust
+///use mp3lame_encoder::{Builder, DualPcm, FlushNoGap, FrameSplitter, Mp3Chunk};
+///
+///let mut mp3_encoder = Builder::new().expect("Create LAME builder").build().expect("To initialize LAME encoder");
+///let mut frames = FrameSplitter::new(&mp3_encoder);
+///
+///let pcm = [0i16; 4_410];
+///mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut frames).expect("To encode");
+///mp3_encoder.flush_into::<FlushNoGap>(&mut frames).expect("To flush");
+///
+///while let Some(chunk) = frames.next_chunk() {
+///    if let Mp3Chunk::Frame(frame) = chunk {
+///        assert_eq!(frame.data.len(), frame.header.frame_len());
+///    }
+///}
+///```
+pub struct FrameSplitter {
+    buffer: Vec<u8>,
+    consumed: usize,
+    sample_position: u64,
+    byte_position: u64,
+    is_lame_tag_pending: bool,
+}
+
+impl FrameSplitter {
+    #[inline]
+    ///Creates new instance for use with `encoder`
+    ///
+    ///Must be created before encoding any data.
+    pub fn new<C: Channels>(encoder: &Encoder<C>) -> Self {
+        Self {
+            buffer: Vec::new(),
+            consumed: 0,
+            sample_position: 0,
+            byte_position: 0,
+            is_lame_tag_pending: encoder.is_lame_tag_written(),
+        }
+    }
+
+    #[inline(always)]
+    ///Returns position of the next sample within the stream.
+    pub fn sample_position(&self) -> u64 {
+        self.sample_position
+    }
+
+    #[inline(always)]
+    ///Returns position of the next byte within the stream.
+    pub fn byte_position(&self) -> u64 {
+        self.byte_position
+    }
+
+    #[inline(always)]
+    ///Returns data that is not yet retrieved
+    pub fn pending(&self) -> &[u8] {
+        &self.buffer[self.consumed..]
+    }
+
+    #[inline(always)]
+    fn consume(&mut self, len: usize) -> &[u8] {
+        let start = self.consumed;
+        self.consumed += len;
+        self.byte_position = self.byte_position.saturating_add(len as u64);
+        &self.buffer[start..self.consumed]
+    }
+
+    ///Retrieves next complete chunk of MP3 stream, if available.
+    pub fn next_chunk(&mut self) -> Option<Mp3Chunk<'_>> {
+        let data = self.pending();
+        if data.len() < FRAME_HEADER_SIZE {
+            return None;
+        }
+
+        if data.starts_with(b"ID3") {
+            if data.len() < ID3V2_HEADER_SIZE {
+                return None;
+            }
+            //Size is syncsafe integer, excluding header and footer
+            let size = data[6..10].iter().fold(0usize, |size, byte| (size << 7) | (*byte & 0x7F) as usize);
+            let footer_size = if data[5] & 0x10 == 0x10 {
+                ID3V2_HEADER_SIZE
+            } else {
+                0
+            };
+            let len = ID3V2_HEADER_SIZE + size + footer_size;
+            if data.len() < len {
+                return None;
+            }
+            return Some(Mp3Chunk::Id3Tag(self.consume(len)));
+        } else if data.starts_with(b"TAG") {
+            if data.len() < ID3V1_TAG_SIZE {
+                return None;
+            }
+            return Some(Mp3Chunk::Id3Tag(self.consume(ID3V1_TAG_SIZE)));
+        }
+
+        match FrameHeader::parse(data) {
+            Some(header) => {
+                let len = header.frame_len();
+                if data.len() < len {
+                    return None;
+                }
+
+                if self.is_lame_tag_pending {
+                    self.is_lame_tag_pending = false;
+                    return Some(Mp3Chunk::LameTag(self.consume(len)));
+                }
+
+                let sample_position = self.sample_position;
+                let byte_position = self.byte_position;
+                self.sample_position = self.sample_position.saturating_add(header.samples_num() as u64);
+                Some(Mp3Chunk::Frame(Mp3Frame {
+                    header,
+                    data: self.consume(len),
+                    sample_position,
+                    byte_position,
+                }))
+            },
+            None => {
+                //Skip until something recognizable
+                let len = (1..data.len()).find(|idx| {
+                    let data = &data[*idx..];
+                    data.starts_with(b"ID3") || data.starts_with(b"TAG") || FrameHeader::parse(data).is_some()
+                }).unwrap_or(data.len());
+                Some(Mp3Chunk::Unknown(self.consume(len)))
+            }
+        }
+    }
+}
+
+impl EncoderOutput for FrameSplitter {
+    #[inline]
+    fn reserve_spare(&mut self, additional: usize) -> &mut [MaybeUninit<u8>] {
+        if self.consumed > 0 {
+            self.buffer.drain(..self.consumed);
+            self.consumed = 0;
+        }
+        self.buffer.reserve_spare(additional)
+    }
+
+    #[inline(always)]
+    unsafe fn advance(&mut self, len: usize) {
+        self.buffer.advance(len)
+    }
+}
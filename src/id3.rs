@@ -0,0 +1,335 @@
+use super::{ffi, Id3TagError, MAX_ALBUM_ART_SIZE};
+
+use alloc::vec::Vec;
+use core::ffi::c_int;
+use core::iter;
+
+//Byte order mark, required by LAME for UTF-16 text.
+const UTF16_BOM: u16 = 0xFEFF;
+//Language of comment frame, when it is unknown.
+const UNKNOWN_LANG: &[u8] = b"XXX\0";
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///ID3 tag versions to write
+pub enum Id3Version {
+    ///Write only ID3v1 tag
+    V1Only,
+    ///Write only ID3v2 tag
+    V2Only,
+    ///Write both ID3v1 and ID3v2 tags
+    Both,
+}
+
+impl Default for Id3Version {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::Both
+    }
+}
+
+#[derive(Clone, Default)]
+///ID3 tag builder, supporting arbitrary text.
+///
+///Latin-1 text is written as it is (making it also available in ID3v1 tag),
+///while any other text is written as UTF-16 (ID3v2 only).
+///
+///Methods prefixed with `with_*` return Self for convenience.
+pub struct Id3TagBuilder<'a> {
+    title: Option<&'a str>,
+    artist: Option<&'a str>,
+    album: Option<&'a str>,
+    album_art: &'a [u8],
+    year: Option<&'a str>,
+    comment: Option<&'a str>,
+    genre: Option<&'a str>,
+    track: Option<(u16, Option<u16>)>,
+    disc: Option<(u16, Option<u16>)>,
+    user_text: Vec<(&'a str, &'a str)>,
+    frames: Vec<(&'a str, &'a str)>,
+    version: Id3Version,
+    padding: Option<usize>,
+}
+
+impl<'a> Id3TagBuilder<'a> {
+    #[inline(always)]
+    ///Creates empty tag
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[inline(always)]
+    ///Sets track's title
+    pub fn with_title(mut self, title: &'a str) -> Self {
+        self.title = Some(title);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets artist name
+    pub fn with_artist(mut self, artist: &'a str) -> Self {
+        self.artist = Some(artist);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets album name
+    pub fn with_album(mut self, album: &'a str) -> Self {
+        self.album = Some(album);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets album art
+    ///
+    ///Must be image data.
+    ///
+    ///Allowed formats: PNG, JPG, GIF
+    ///
+    ///Maximum size is defined by constant MAX_ALBUM_ART_SIZE
+    pub fn with_album_art(mut self, album_art: &'a [u8]) -> Self {
+        self.album_art = album_art;
+        self
+    }
+
+    #[inline(always)]
+    ///Sets year
+    pub fn with_year(mut self, year: &'a str) -> Self {
+        self.year = Some(year);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets comment
+    pub fn with_comment(mut self, comment: &'a str) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets genre
+    ///
+    ///Genre can be specified either by name (case insensitive) or number from ID3v1 genre list.
+    ///Unknown names are written as custom genre in ID3v2 tag.
+    pub fn with_genre(mut self, genre: &'a str) -> Self {
+        self.genre = Some(genre);
+        self
+    }
+
+    #[inline(always)]
+    ///Sets track number with optional total number of tracks
+    ///
+    ///ID3v1 tag holds only numbers within `1..=255`, other numbers are written only to ID3v2 tag.
+    pub fn with_track(mut self, number: u16, total: Option<u16>) -> Self {
+        self.track = Some((number, total));
+        self
+    }
+
+    #[inline(always)]
+    ///Sets disc number with optional total number of discs
+    pub fn with_disc(mut self, number: u16, total: Option<u16>) -> Self {
+        self.disc = Some((number, total));
+        self
+    }
+
+    #[inline(always)]
+    ///Adds user defined text (`TXXX` frame)
+    ///
+    ///`description` must not contain `=`
+    pub fn with_user_text(mut self, description: &'a str, value: &'a str) -> Self {
+        self.user_text.push((description, value));
+        self
+    }
+
+    #[inline(always)]
+    ///Adds arbitrary ID3v2 text frame
+    ///
+    ///`id` must be four character frame id (e.g. `TCOM`)
+    pub fn with_frame(mut self, id: &'a str, value: &'a str) -> Self {
+        self.frames.push((id, value));
+        self
+    }
+
+    #[inline(always)]
+    ///Sets which versions of ID3 tag to write
+    ///
+    ///Defaults to [Id3Version::Both]
+    pub fn with_version(mut self, version: Id3Version) -> Self {
+        self.version = version;
+        self
+    }
+
+    #[inline(always)]
+    ///Sets number of padding bytes to add after ID3v2 tag
+    ///
+    ///Defaults to LAME's choice.
+    pub fn with_padding(mut self, padding: usize) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    #[inline]
+    ///Returns true if any is set
+    pub fn is_any_set(&self) -> bool {
+        self.title.is_some() || self.artist.is_some() || self.album.is_some() || !self.album_art.is_empty() || self.year.is_some()
+            || self.comment.is_some() || self.genre.is_some() || self.track.is_some() || self.disc.is_some()
+            || !self.user_text.is_empty() || !self.frames.is_empty()
+    }
+
+    pub(crate) fn apply(&self, ptr: *mut ffi::lame_global_flags) -> Result<(), Id3TagError> {
+        if self.album_art.len() > MAX_ALBUM_ART_SIZE {
+            return Err(Id3TagError::AlbumArtOverflow);
+        }
+        for (id, _) in self.frames.iter() {
+            if !is_frame_id(id) {
+                return Err(Id3TagError::InvalidFrameId);
+            }
+        }
+        for (description, _) in self.user_text.iter() {
+            if description.contains('=') {
+                return Err(Id3TagError::InvalidValue);
+            }
+        }
+
+        unsafe {
+            ffi::id3tag_init(ptr);
+            match self.version {
+                Id3Version::V1Only => ffi::id3tag_v1_only(ptr),
+                Id3Version::V2Only => ffi::id3tag_v2_only(ptr),
+                Id3Version::Both => ffi::id3tag_add_v2(ptr),
+            }
+            if let Some(padding) = self.padding {
+                ffi::id3tag_set_pad(ptr, padding);
+            }
+
+            if !self.album_art.is_empty() {
+                check(ffi::id3tag_set_albumart(ptr, self.album_art.as_ptr() as _, self.album_art.len()))?;
+            }
+
+            if let Some(title) = self.title {
+                set_text(ptr, b"TIT2\0", title, |ptr, text| {
+                    ffi::id3tag_set_title(ptr, text);
+                    0
+                })?;
+            }
+            if let Some(artist) = self.artist {
+                set_text(ptr, b"TPE1\0", artist, |ptr, text| {
+                    ffi::id3tag_set_artist(ptr, text);
+                    0
+                })?;
+            }
+            if let Some(album) = self.album {
+                set_text(ptr, b"TALB\0", album, |ptr, text| {
+                    ffi::id3tag_set_album(ptr, text);
+                    0
+                })?;
+            }
+            if let Some(year) = self.year {
+                set_text(ptr, b"TYER\0", year, |ptr, text| {
+                    ffi::id3tag_set_year(ptr, text);
+                    0
+                })?;
+            }
+            if let Some(genre) = self.genre {
+                set_text(ptr, b"TCON\0", genre, |ptr, text| {
+                    match ffi::id3tag_set_genre(ptr, text) {
+                        //Unknown genre name is stored as custom genre
+                        -2 => 0,
+                        code => code,
+                    }
+                }).map_err(|error| match error {
+                    //Genre number is out of range
+                    Id3TagError::Other(-1) => Id3TagError::InvalidGenre,
+                    error => error,
+                })?;
+            }
+            if let Some(comment) = self.comment {
+                if is_latin1(comment) {
+                    let comment = to_latin1(comment);
+                    ffi::id3tag_set_comment(ptr, comment.as_ptr() as _);
+                } else {
+                    let description = to_utf16("");
+                    let comment = to_utf16(comment);
+                    check(ffi::id3tag_set_comment_utf16(ptr, UNKNOWN_LANG.as_ptr() as _, description.as_ptr(), comment.as_ptr()))?;
+                }
+            }
+
+            if let Some((number, total)) = self.track {
+                let track = format_number(number, total);
+                match ffi::id3tag_set_track(ptr, track.as_ptr() as _) {
+                    //Number outside of ID3v1 range is written only to ID3v2 tag
+                    -1 => (),
+                    code => check(code)?,
+                }
+            }
+            if let Some((number, total)) = self.disc {
+                let disc = format_number(number, total);
+                check(ffi::id3tag_set_textinfo_latin1(ptr, b"TPOS\0".as_ptr() as _, disc.as_ptr() as _))?;
+            }
+
+            for (description, value) in self.user_text.iter() {
+                let text = alloc::format!("{description}={value}");
+                set_text(ptr, b"TXXX\0", &text, |ptr, text| {
+                    ffi::id3tag_set_textinfo_latin1(ptr, b"TXXX\0".as_ptr() as _, text)
+                })?;
+            }
+
+            for (id, value) in self.frames.iter() {
+                let mut frame_id = [0u8; 5];
+                frame_id[..4].copy_from_slice(id.as_bytes());
+                set_text(ptr, &frame_id, value, |ptr, text| {
+                    ffi::id3tag_set_textinfo_latin1(ptr, frame_id.as_ptr() as _, text)
+                })?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn check(code: c_int) -> Result<(), Id3TagError> {
+    if code >= 0 {
+        Ok(())
+    } else {
+        Err(Id3TagError::Other(code))
+    }
+}
+
+#[inline]
+fn is_frame_id(id: &str) -> bool {
+    id.len() == 4 && id.bytes().all(|byte| byte.is_ascii_uppercase() || byte.is_ascii_digit())
+}
+
+#[inline]
+fn is_latin1(text: &str) -> bool {
+    text.chars().all(|ch| (ch as u32) <= 0xFF)
+}
+
+//Text must be latin1, otherwise it is truncated
+fn to_latin1(text: &str) -> Vec<u8> {
+    text.chars().map(|ch| ch as u8).chain(iter::once(0)).collect()
+}
+
+fn to_utf16(text: &str) -> Vec<u16> {
+    iter::once(UTF16_BOM).chain(text.encode_utf16()).chain(iter::once(0)).collect()
+}
+
+//Writes latin1 text using `latin1` setter or falls back to UTF-16 frame `id`
+unsafe fn set_text(ptr: *mut ffi::lame_global_flags, id: &[u8; 5], text: &str, latin1: impl FnOnce(*mut ffi::lame_global_flags, *const core::ffi::c_char) -> c_int) -> Result<(), Id3TagError> {
+    if is_latin1(text) {
+        let text = to_latin1(text);
+        check(latin1(ptr, text.as_ptr() as _))
+    } else {
+        let text = to_utf16(text);
+        check(ffi::id3tag_set_textinfo_utf16(ptr, id.as_ptr() as _, text.as_ptr()))
+    }
+}
+
+//Formats `number[/total]` as null terminated string
+fn format_number(number: u16, total: Option<u16>) -> alloc::string::String {
+    match total {
+        Some(total) => alloc::format!("{number}/{total}\0"),
+        None => alloc::format!("{number}\0"),
+    }
+}
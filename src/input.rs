@@ -1,13 +1,157 @@
-use super::{Encoder, ffi};
+use super::{max_required_buffer_size, Encoder, EncodeError, ffi};
 
-use core::ptr;
+use core::{cmp, ptr};
 use core::ffi::{c_int, c_long};
 
+//Number of samples per channel, that is converted at once by inputs lacking direct support in LAME.
+const CONVERT_CHUNK_SIZE: usize = 1152;
+
+//Sample type, that inputs lacking direct support in LAME are converted into.
+pub(crate) trait ConvertedSample: Copy {
+    const ZERO: Self;
+
+    //Encodes `left` and optionally `right` channels
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int;
+}
+
+//Full scale integer
+impl ConvertedSample for c_int {
+    const ZERO: Self = 0;
+
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer_int(encoder.ptr(), left.as_ptr(), right.map_or(ptr::null(), <[Self]>::as_ptr), left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+//Float within range `-1.0..=1.0`
+impl ConvertedSample for f32 {
+    const ZERO: Self = 0.0;
+
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer_ieee_float(encoder.ptr(), left.as_ptr(), right.map_or(ptr::null(), <[Self]>::as_ptr), left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+//Encodes `samples_num` samples per channel, converting them chunk by chunk into `T`
+//
+//`convert` is called with offset of chunk and buffers to fill (`right` is ignored if `is_stereo` is false).
+//
+//Once LAME consumes chunk, it cannot be undone, hence output buffer must fit worst case output of all samples.
+//Output of every chunk fits then, as LAME's worst case applies to output of consecutive calls as well.
+pub(crate) fn encode_converted<T: ConvertedSample>(encoder: &mut Encoder, samples_num: usize, is_stereo: bool, output_buf: *mut u8, output_len: usize, mut convert: impl FnMut(usize, &mut [T], &mut [T])) -> c_int {
+    if output_len < encoder.with_id3v2_tag_size(max_required_buffer_size(samples_num)) {
+        return -1;
+    }
+
+    let mut left = [T::ZERO; CONVERT_CHUNK_SIZE];
+    let mut right = [T::ZERO; CONVERT_CHUNK_SIZE];
+    let mut offset = 0;
+    let mut written = 0;
+
+    while offset < samples_num {
+        let len = cmp::min(CONVERT_CHUNK_SIZE, samples_num - offset);
+        convert(offset, &mut left[..len], &mut right[..len]);
+        let right = if is_stereo {
+            Some(&right[..len])
+        } else {
+            None
+        };
+
+        let result = T::encode_planes(encoder, &left[..len], right, unsafe { output_buf.add(written) }, output_len - written);
+        if result < 0 {
+            return result;
+        }
+
+        written += result as usize;
+        offset += len;
+    }
+
+    written as c_int
+}
+
+#[inline]
+fn validate_mono(num_channels: u8) -> Result<(), EncodeError> {
+    if num_channels == 1 {
+        Ok(())
+    } else {
+        Err(EncodeError::ChannelMismatch)
+    }
+}
+
+#[inline]
+fn validate_dual(num_channels: u8, left: usize, right: usize) -> Result<(), EncodeError> {
+    if num_channels != 2 {
+        Err(EncodeError::ChannelMismatch)
+    } else if left != right {
+        Err(EncodeError::UnequalChannelLengths)
+    } else {
+        Ok(())
+    }
+}
+
+//`sample_size` is size of single sample in bytes
+#[inline]
+fn validate_packed(len: usize, sample_size: usize) -> Result<(), EncodeError> {
+    if len % sample_size == 0 {
+        Ok(())
+    } else {
+        Err(EncodeError::IncompleteSample)
+    }
+}
+
+//`frame_size` is size of sample for both channels
+#[inline]
+fn validate_interleaved(num_channels: u8, len: usize, frame_size: usize) -> Result<(), EncodeError> {
+    if num_channels != 2 {
+        Err(EncodeError::ChannelMismatch)
+    } else if len % frame_size != 0 {
+        Err(EncodeError::OddInterleavedLength)
+    } else {
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn u8_to_int(sample: u8) -> c_int {
+    ((sample as c_int) - 128) << 24
+}
+
+#[inline(always)]
+fn i8_to_int(sample: i8) -> c_int {
+    (sample as c_int) << 24
+}
+
+#[inline(always)]
+fn i24_to_int(sample: &[u8]) -> c_int {
+    i32::from_le_bytes([0, sample[0], sample[1], sample[2]]) as c_int
+}
+
 ///Type of PCM input for encoder
 ///
 ///Please note that while you can implement your own trait, it is your responsibility to ensure
 ///that `encode` function is correct and safe.
 pub trait EncoderInput {
+    ///Returns number of samples per channel.
+    ///
+    ///Used to determine required output buffer size (see [max_required_buffer_size](crate::max_required_buffer_size)),
+    ///hence it must not be less than actual number of samples.
+    fn samples_num(&self) -> usize;
+
+    ///Validates shape of `self` against number of channels in encoder.
+    ///
+    ///Performed before encoding, so that no invalid input is passed to LAME.
+    ///
+    ///Default implementation performs no validation.
+    fn validate(&self, _num_channels: u8) -> Result<(), EncodeError> {
+        Ok(())
+    }
+
     ///Encodes `self` using provided encoder.
     ///
     ///## Arguments
@@ -24,9 +168,20 @@ pub trait EncoderInput {
 ///PCM data with only 1 channel
 ///
 ///In this case, number of samples is always equals to number of samples in slice.
+#[derive(Copy, Clone)]
 pub struct MonoPcm<'a, T>(pub &'a [T]);
 
 impl EncoderInput for MonoPcm<'_, u16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -36,6 +191,16 @@ impl EncoderInput for MonoPcm<'_, u16> {
 }
 
 impl EncoderInput for MonoPcm<'_, i16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -46,6 +211,16 @@ impl EncoderInput for MonoPcm<'_, i16> {
 
 //On most platforms it should be i32
 impl EncoderInput for MonoPcm<'_, c_int> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -58,6 +233,16 @@ impl EncoderInput for MonoPcm<'_, c_int> {
 //On most unix it should be i64.
 //But unclear about other platforms, so it is only implemented there as otherwise it is i32.
 impl EncoderInput for MonoPcm<'_, c_long> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -67,6 +252,16 @@ impl EncoderInput for MonoPcm<'_, c_long> {
 }
 
 impl EncoderInput for MonoPcm<'_, f32> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -76,6 +271,16 @@ impl EncoderInput for MonoPcm<'_, f32> {
 }
 
 impl EncoderInput for MonoPcm<'_, f64> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         unsafe {
@@ -84,13 +289,56 @@ impl EncoderInput for MonoPcm<'_, f64> {
     }
 }
 
+//8-bit PCM is unsigned
+impl EncoderInput for MonoPcm<'_, u8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        encode_converted(encoder, self.0.len(), false, output_buf, output_len, |offset, left, _| {
+            for (dst, src) in left.iter_mut().zip(&self.0[offset..]) {
+                *dst = u8_to_int(*src);
+            }
+        })
+    }
+}
+
+impl EncoderInput for MonoPcm<'_, i8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        encode_converted(encoder, self.0.len(), false, output_buf, output_len, |offset, left, _| {
+            for (dst, src) in left.iter_mut().zip(&self.0[offset..]) {
+                *dst = i8_to_int(*src);
+            }
+        })
+    }
+}
+
 ///PCM data represented by two channels.
 ///
 ///Number of samples must be equal between left and right channels.
 ///
 ///If you want to feed encoder single PCM data, then use [MonoPcm](MonoPcm)
-///In case length of channels is not equal, it will always feed encoder minimum of both length.
-///In debug mode it will panic in this case also to warn you of error.
+///In case length of channels is not equal, encoding fails with [EncodeError::UnequalChannelLengths].
+#[derive(Copy, Clone)]
 pub struct DualPcm<'a, T> {
     ///left channel PCM data
     pub left: &'a [T],
@@ -99,6 +347,16 @@ pub struct DualPcm<'a, T> {
 }
 
 impl EncoderInput for DualPcm<'_, i16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         debug_assert_eq!(self.left.len(), self.right.len());
@@ -110,6 +368,16 @@ impl EncoderInput for DualPcm<'_, i16> {
 }
 
 impl EncoderInput for DualPcm<'_, u16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         debug_assert_eq!(self.left.len(), self.right.len());
@@ -121,6 +389,16 @@ impl EncoderInput for DualPcm<'_, u16> {
 }
 
 impl EncoderInput for DualPcm<'_, c_int> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         debug_assert_eq!(self.left.len(), self.right.len());
@@ -132,6 +410,16 @@ impl EncoderInput for DualPcm<'_, c_int> {
 }
 
 impl EncoderInput for DualPcm<'_, f32> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         debug_assert_eq!(self.left.len(), self.right.len());
@@ -143,6 +431,16 @@ impl EncoderInput for DualPcm<'_, f32> {
 }
 
 impl EncoderInput for DualPcm<'_, f64> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         debug_assert_eq!(self.left.len(), self.right.len());
@@ -153,15 +451,74 @@ impl EncoderInput for DualPcm<'_, f64> {
     }
 }
 
+impl EncoderInput for DualPcm<'_, u8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        debug_assert_eq!(self.left.len(), self.right.len());
+        let samples_num = core::cmp::min(self.left.len(), self.right.len());
+        encode_converted(encoder, samples_num, true, output_buf, output_len, |offset, left, right| {
+            for (idx, (left, right)) in left.iter_mut().zip(right.iter_mut()).enumerate() {
+                *left = u8_to_int(self.left[offset + idx]);
+                *right = u8_to_int(self.right[offset + idx]);
+            }
+        })
+    }
+}
+
+impl EncoderInput for DualPcm<'_, i8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len())
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        debug_assert_eq!(self.left.len(), self.right.len());
+        let samples_num = core::cmp::min(self.left.len(), self.right.len());
+        encode_converted(encoder, samples_num, true, output_buf, output_len, |offset, left, right| {
+            for (idx, (left, right)) in left.iter_mut().zip(right.iter_mut()).enumerate() {
+                *left = i8_to_int(self.left[offset + idx]);
+                *right = i8_to_int(self.right[offset + idx]);
+            }
+        })
+    }
+}
+
 ///PCM data in interleaved form
 ///
 ///Interleaved input assumes you have two channels encoded within continuous buffer as sequence pairs: `[<left>, <right>...]`
 ///Hence, number of samples is always `data.len() / 2`.
 ///
-///If it is not your case, encoding will panic in debug mode, but otherwise you most likely to get incomplete output.
+///If it is not your case, encoding fails with [EncodeError::OddInterleavedLength].
+#[derive(Copy, Clone)]
 pub struct InterleavedPcm<'a, T>(pub &'a [T]);
 
 impl EncoderInput for InterleavedPcm<'_, i16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         let samples_num = self.0.len() / 2;
@@ -174,6 +531,16 @@ impl EncoderInput for InterleavedPcm<'_, i16> {
 }
 
 impl EncoderInput for InterleavedPcm<'_, u16> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         let samples_num = self.0.len() / 2;
@@ -186,6 +553,16 @@ impl EncoderInput for InterleavedPcm<'_, u16> {
 }
 
 impl EncoderInput for InterleavedPcm<'_, c_int> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         let samples_num = self.0.len() / 2;
@@ -197,6 +574,16 @@ impl EncoderInput for InterleavedPcm<'_, c_int> {
 }
 
 impl EncoderInput for InterleavedPcm<'_, f32> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         let samples_num = self.0.len() / 2;
@@ -208,6 +595,16 @@ impl EncoderInput for InterleavedPcm<'_, f32> {
 }
 
 impl EncoderInput for InterleavedPcm<'_, f64> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
     #[inline(always)]
     fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
         let samples_num = self.0.len() / 2;
@@ -218,6 +615,160 @@ impl EncoderInput for InterleavedPcm<'_, f64> {
     }
 }
 
+impl EncoderInput for InterleavedPcm<'_, u8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        let samples_num = self.0.len() / 2;
+        debug_assert_eq!(self.0.len() % 2, 0);
+        encode_converted(encoder, samples_num, true, output_buf, output_len, |offset, left, right| {
+            let input = self.0[offset * 2..].chunks_exact(2);
+            for ((left, right), input) in left.iter_mut().zip(right.iter_mut()).zip(input) {
+                *left = u8_to_int(input[0]);
+                *right = u8_to_int(input[1]);
+            }
+        })
+    }
+}
+
+impl EncoderInput for InterleavedPcm<'_, i8> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 2
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 2)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        let samples_num = self.0.len() / 2;
+        debug_assert_eq!(self.0.len() % 2, 0);
+        encode_converted(encoder, samples_num, true, output_buf, output_len, |offset, left, right| {
+            let input = self.0[offset * 2..].chunks_exact(2);
+            for ((left, right), input) in left.iter_mut().zip(right.iter_mut()).zip(input) {
+                *left = i8_to_int(input[0]);
+                *right = i8_to_int(input[1]);
+            }
+        })
+    }
+}
+
+///Packed 24-bit signed little endian PCM data with only 1 channel
+///
+///Each sample takes 3 bytes, hence number of samples is `data.len() / 3`.
+///Data must contain only complete samples, otherwise [EncodeError::IncompleteSample] is reported.
+///
+///Samples are converted in small chunks, without allocating intermediate buffer.
+#[derive(Copy, Clone)]
+pub struct MonoPcm24<'a>(pub &'a [u8]);
+
+impl EncoderInput for MonoPcm24<'_> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 3
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_mono(num_channels)?;
+        validate_packed(self.0.len(), 3)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        encode_converted(encoder, self.samples_num(), false, output_buf, output_len, |offset, left, _| {
+            let input = self.0[offset * 3..].chunks_exact(3);
+            for (left, input) in left.iter_mut().zip(input) {
+                *left = i24_to_int(input);
+            }
+        })
+    }
+}
+
+///Packed 24-bit signed little endian PCM data represented by two channels.
+///
+///Each sample takes 3 bytes, hence number of samples is `data.len() / 3`.
+///Refer to [DualPcm] for requirements on channels length.
+///Channels must contain only complete samples, otherwise [EncodeError::IncompleteSample] is reported.
+///
+///Samples are converted in small chunks, without allocating intermediate buffer.
+#[derive(Copy, Clone)]
+pub struct DualPcm24<'a> {
+    ///left channel PCM data
+    pub left: &'a [u8],
+    ///right channel PCM data
+    pub right: &'a [u8],
+}
+
+impl EncoderInput for DualPcm24<'_> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        core::cmp::min(self.left.len(), self.right.len()) / 3
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_dual(num_channels, self.left.len(), self.right.len())?;
+        validate_packed(self.left.len(), 3)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        encode_converted(encoder, self.samples_num(), true, output_buf, output_len, |offset, left, right| {
+            let left_input = self.left[offset * 3..].chunks_exact(3);
+            let right_input = self.right[offset * 3..].chunks_exact(3);
+            for ((left, right), (left_input, right_input)) in left.iter_mut().zip(right.iter_mut()).zip(left_input.zip(right_input)) {
+                *left = i24_to_int(left_input);
+                *right = i24_to_int(right_input);
+            }
+        })
+    }
+}
+
+///Packed 24-bit signed little endian PCM data in interleaved form
+///
+///Each sample takes 3 bytes, hence number of samples is `data.len() / 6`.
+///Refer to [InterleavedPcm] for requirements on layout.
+///
+///Samples are converted in small chunks, without allocating intermediate buffer.
+#[derive(Copy, Clone)]
+pub struct InterleavedPcm24<'a>(pub &'a [u8]);
+
+impl EncoderInput for InterleavedPcm24<'_> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.0.len() / 6
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_interleaved(num_channels, self.0.len(), 6)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        encode_converted(encoder, self.samples_num(), true, output_buf, output_len, |offset, left, right| {
+            let input = self.0[offset * 6..].chunks_exact(6);
+            for ((left, right), input) in left.iter_mut().zip(right.iter_mut()).zip(input) {
+                *left = i24_to_int(&input[..3]);
+                *right = i24_to_int(&input[3..]);
+            }
+        })
+    }
+}
+
 ///Flush method.
 pub trait EncoderFlush {
     ///Performs flush, returning result as signed integer.
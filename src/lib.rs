@@ -85,6 +85,7 @@ extern crate alloc;
 pub use mp3lame_sys as ffi;
 
 use alloc::vec::Vec;
+use core::marker::PhantomData;
 use core::mem::{self, MaybeUninit};
 use core::num::{NonZeroU32, NonZeroUsize};
 use core::ptr::{self, NonNull};
@@ -93,10 +94,52 @@ use core::ffi::c_int;
 
 mod input;
 pub use input::*;
+mod raw;
+pub use raw::*;
+mod channels;
+pub use channels::*;
+mod output;
+pub use output::*;
+mod id3;
+pub use id3::*;
+mod frame;
+pub use frame::*;
+mod config;
+pub use config::*;
+mod settings;
+pub use settings::*;
+mod stats;
+pub use stats::*;
+#[cfg(feature = "decoder")]
+mod decoder;
+#[cfg(feature = "decoder")]
+pub use decoder::*;
+#[cfg(feature = "std")]
+mod writer;
+#[cfg(feature = "std")]
+pub use writer::Mp3Writer;
+#[cfg(feature = "std")]
+mod wav;
+#[cfg(feature = "std")]
+pub use wav::*;
+#[cfg(feature = "tokio")]
+mod async_writer;
+#[cfg(feature = "tokio")]
+pub use async_writer::*;
+#[cfg(feature = "symphonia")]
+mod symphonia;
 
 ///Maximum size of album art
 pub const MAX_ALBUM_ART_SIZE: usize = 128 * 1024;
 
+//Size of ID3v1 tag that can be written on flush
+const ID3V1_TAG_SIZE: usize = 128;
+
+//Values of LAME's strict_ISO, which limits size of frame buffer
+const MDB_STRICT_ISO: c_int = 1;
+//LAME's default
+const MDB_MAXIMUM: c_int = 2;
+
 ///Calculates maximum required size for specified number of samples.
 ///
 ///Note that actual requirement may vary depending on encoder parameters,
@@ -115,6 +158,31 @@ pub const fn max_required_buffer_size(sample_number: usize) -> usize {
 pub enum Id3TagError {
     ///Specified buffer exceed limit of 128kb
     AlbumArtOverflow,
+    ///Frame id is not valid ID3v2 frame id.
+    InvalidFrameId,
+    ///Genre number is not within ID3v1 genre list.
+    InvalidGenre,
+    ///Value is not valid for the tag.
+    InvalidValue,
+    ///Other errors, most likely unexpected.
+    Other(c_int),
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Id3TagError {
+}
+
+impl fmt::Display for Id3TagError {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::AlbumArtOverflow => fmt.write_fmt(format_args!("album art exceeds {MAX_ALBUM_ART_SIZE} bytes")),
+            Self::InvalidFrameId => fmt.write_str("invalid frame id"),
+            Self::InvalidGenre => fmt.write_str("invalid genre"),
+            Self::InvalidValue => fmt.write_str("invalid value"),
+            Self::Other(code) => fmt.write_fmt(format_args!("error code={code}")),
+        }
+    }
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq)]
@@ -130,6 +198,35 @@ pub enum BuildError {
     BadSampleFreq,
     ///Internal error
     InternalError,
+    ///Output sample rate is not supported by MPEG Layer III
+    UnsupportedSampleRate(u32),
+    ///Bitrate is not allowed for MPEG version of output sample rate
+    BitrateNotAllowed {
+        ///Bitrate (as kbps)
+        bitrate: u16,
+        ///MPEG version
+        version: MpegVersion,
+        ///Output sample rate
+        sample_rate: u32,
+    },
+    ///Filter frequency is not below Nyquist frequency of sample rate
+    FilterAboveNyquist {
+        ///Filter frequency
+        frequency: u32,
+        ///Sample rate, used to encode
+        sample_rate: u32,
+    },
+    ///Gain is not finite or linear gain is negative
+    InvalidGain,
+    ///ID3 tag cannot be set
+    Id3Tag(Id3TagError),
+    ///Option cannot be used together with another option
+    IncompatibleOptions {
+        ///Option, that is set
+        option: &'static str,
+        ///Option, that conflicts with it
+        conflict: &'static str,
+    },
     ///Other errors, most likely unexpected.
     Other(c_int),
 }
@@ -165,6 +262,12 @@ impl fmt::Display for BuildError {
             Self::BadBRate => fmt.write_str("bad bitrate"),
             Self::BadSampleFreq => fmt.write_str("bad sample frequency"),
             Self::InternalError => fmt.write_str("internal error"),
+            Self::UnsupportedSampleRate(sample_rate) => fmt.write_fmt(format_args!("sample rate {sample_rate} Hz is not supported")),
+            Self::BitrateNotAllowed { bitrate, version, sample_rate } => fmt.write_fmt(format_args!("bitrate {bitrate} not allowed for {version} {sample_rate} Hz")),
+            Self::FilterAboveNyquist { frequency, sample_rate } => fmt.write_fmt(format_args!("filter frequency {frequency} Hz is not below Nyquist frequency of {sample_rate} Hz")),
+            Self::InvalidGain => fmt.write_str("invalid gain"),
+            Self::Id3Tag(error) => fmt.write_fmt(format_args!("invalid ID3 tag: {error}")),
+            Self::IncompatibleOptions { option, conflict } => fmt.write_fmt(format_args!("{option} cannot be used with {conflict}")),
             Self::Other(code) => fmt.write_fmt(format_args!("error code={code}")),
         }
     }
@@ -186,11 +289,25 @@ pub enum EncodeError {
     InvalidState,
     ///Psycho acoustic problems, whatever it means.
     PsychoAcoustic,
+    ///Input has number of channels, that cannot be encoded (i.e. more than 2)
+    UnsupportedChannels,
+    ///Input's number of channels doesn't match encoder's [num_channels](Encoder::num_channels)
+    ChannelMismatch,
+    ///Channels of input have different number of samples
+    UnequalChannelLengths,
+    ///Interleaved input has incomplete sample for last channel
+    OddInterleavedLength,
+    ///Input ends with incomplete sample (i.e. packed 24-bit samples)
+    IncompleteSample,
     ///Other errors, most likely unexpected.
     Other(c_int),
 }
 
 impl EncodeError {
+    //Codes reported by this crate's inputs, outside of range used by LAME
+    const UNSUPPORTED_CHANNELS: c_int = -100;
+    const CHANNEL_MISMATCH: c_int = -101;
+
     #[inline(always)]
     fn from_c_int(code: c_int) -> Result<usize, Self> {
         if code >= 0 {
@@ -202,6 +319,8 @@ impl EncodeError {
             -2 => Err(Self::NoMem),
             -3 => Err(Self::InvalidState),
             -4 => Err(Self::PsychoAcoustic),
+            Self::UNSUPPORTED_CHANNELS => Err(Self::UnsupportedChannels),
+            Self::CHANNEL_MISMATCH => Err(Self::ChannelMismatch),
             _ => Err(Self::Other(code)),
         }
     }
@@ -219,6 +338,11 @@ impl fmt::Display for EncodeError {
             Self::NoMem => fmt.write_str("alloc failure"),
             Self::InvalidState => fmt.write_str("attempt to use uninitialized encoder"),
             Self::PsychoAcoustic => fmt.write_str("psycho acoustic problems"),
+            Self::UnsupportedChannels => fmt.write_str("unsupported number of input channels"),
+            Self::ChannelMismatch => fmt.write_str("number of input channels doesn't match encoder"),
+            Self::UnequalChannelLengths => fmt.write_str("input channels have different length"),
+            Self::OddInterleavedLength => fmt.write_str("interleaved input length is not multiple of channels number"),
+            Self::IncompleteSample => fmt.write_str("input ends with incomplete sample"),
             Self::Other(code) => fmt.write_fmt(format_args!("error code={code}")),
         }
     }
@@ -226,7 +350,10 @@ impl fmt::Display for EncodeError {
 
 
 ///Enumeration of valid values for `set_brate`
-#[derive(Copy, Clone)]
+///
+///Not every value is allowed for every MPEG version, refer to [MpegVersion::bitrates]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u16)]
 pub enum Bitrate {
     ///8_000
@@ -241,6 +368,8 @@ pub enum Bitrate {
     Kbps40 = 40,
     ///48_000
     Kbps48 = 48,
+    ///56_000
+    Kbps56 = 56,
     ///64_000
     Kbps64 = 64,
     ///80_000
@@ -251,6 +380,10 @@ pub enum Bitrate {
     Kbps112 = 112,
     ///128_000
     Kbps128 = 128,
+    ///144_000
+    ///
+    ///Only allowed for MPEG-2 and MPEG-2.5
+    Kbps144 = 144,
     ///160_000
     Kbps160 = 160,
     ///192_000
@@ -266,7 +399,8 @@ pub enum Bitrate {
 ///Alias to `Bitrate` with incorrect spelling
 pub use Bitrate as Birtate;
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 ///Possible VBR types
 pub enum VbrMode {
@@ -282,6 +416,19 @@ pub enum VbrMode {
     Mtrh = ffi::vbr_mode::vbr_mtrh as u8,
 }
 
+impl VbrMode {
+    #[inline(always)]
+    fn from_raw(mode: c_int) -> Self {
+        match mode {
+            ffi::vbr_mode::vbr_off => Self::Off,
+            ffi::vbr_mode::vbr_mt => Self::Mt,
+            ffi::vbr_mode::vbr_rh => Self::Rh,
+            ffi::vbr_mode::vbr_abr => Self::Abr,
+            _ => Self::Mtrh,
+        }
+    }
+}
+
 impl Default for VbrMode {
     #[inline(always)]
     fn default() -> Self {
@@ -289,7 +436,8 @@ impl Default for VbrMode {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 ///Possible modes for encoder
 pub enum Mode {
@@ -307,9 +455,151 @@ pub enum Mode {
     NotSet = ffi::MPEG_mode::NOT_SET as u8,
 }
 
+impl Mode {
+    #[inline(always)]
+    fn from_raw(mode: c_int) -> Self {
+        match mode {
+            mode if mode == ffi::MPEG_mode::MONO as c_int => Self::Mono,
+            mode if mode == ffi::MPEG_mode::STEREO as c_int => Self::Stereo,
+            mode if mode == ffi::MPEG_mode::JOINT_STEREO as c_int => Self::JointStereo,
+            mode if mode == ffi::MPEG_mode::DUAL_CHANNEL as c_int => Self::DaulChannel,
+            _ => Self::NotSet,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(u8)]
+///De-emphasis, that decoder should apply, as indicated by frame header
+pub enum Emphasis {
+    ///No emphasis.
+    None = 0,
+    ///50/15 µs emphasis.
+    Micro50_15 = 1,
+    ///CCITT J.17 emphasis.
+    CcittJ17 = 3,
+}
+
+impl Emphasis {
+    #[inline(always)]
+    fn from_raw(emphasis: c_int) -> Self {
+        match emphasis {
+            1 => Self::Micro50_15,
+            3 => Self::CcittJ17,
+            _ => Self::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///MPEG audio version
+pub enum MpegVersion {
+    ///MPEG-1, used for sample rates `32_000`, `44_100` and `48_000`
+    Mpeg1,
+    ///MPEG-2, used for sample rates `16_000`, `22_050` and `24_000`
+    Mpeg2,
+    ///MPEG-2.5, used for sample rates `8_000`, `11_025` and `12_000`
+    Mpeg25,
+}
+
+impl MpegVersion {
+    ///Determines MPEG version from output sample rate
+    ///
+    ///Returns `None` if sample rate is not supported.
+    pub const fn from_sample_rate(sample_rate: u32) -> Option<Self> {
+        match sample_rate {
+            32_000 | 44_100 | 48_000 => Some(Self::Mpeg1),
+            16_000 | 22_050 | 24_000 => Some(Self::Mpeg2),
+            8_000 | 11_025 | 12_000 => Some(Self::Mpeg25),
+            _ => None,
+        }
+    }
+
+    ///Returns bitrates (as kbps) allowed for Layer III frame of this version
+    pub const fn bitrates(self) -> &'static [u16] {
+        match self {
+            Self::Mpeg1 => &[32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320],
+            Self::Mpeg2 | Self::Mpeg25 => &[8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160],
+        }
+    }
+
+    //Checks that bitrate is one of allowed for this version
+    fn check_bitrate(self, sample_rate: u32, bitrate: c_int) -> Result<(), BuildError> {
+        match u16::try_from(bitrate) {
+            Ok(bitrate) if self.bitrates().contains(&bitrate) => Ok(()),
+            _ => Err(BuildError::BitrateNotAllowed {
+                bitrate: bitrate.try_into().unwrap_or(u16::MAX),
+                version: self,
+                sample_rate,
+            }),
+        }
+    }
+
+    //Checks that bitrate is within range of allowed bitrates for this version
+    fn check_bitrate_range(self, sample_rate: u32, bitrate: c_int) -> Result<(), BuildError> {
+        let bitrates = self.bitrates();
+        if bitrate >= bitrates[0] as c_int && bitrate <= bitrates[bitrates.len() - 1] as c_int {
+            Ok(())
+        } else {
+            Err(BuildError::BitrateNotAllowed {
+                bitrate: bitrate.try_into().unwrap_or(u16::MAX),
+                version: self,
+                sample_rate,
+            })
+        }
+    }
+}
+
+impl fmt::Display for MpegVersion {
+    #[inline]
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Mpeg1 => fmt.write_str("MPEG-1"),
+            Self::Mpeg2 => fmt.write_str("MPEG-2"),
+            Self::Mpeg25 => fmt.write_str("MPEG-2.5"),
+        }
+    }
+}
+
+impl Bitrate {
+    ///Creates bitrate from kbps, if it is one of listed values
+    pub const fn from_kbps(kbps: u16) -> Option<Self> {
+        match kbps {
+            8 => Some(Self::Kbps8),
+            16 => Some(Self::Kbps16),
+            24 => Some(Self::Kbps24),
+            32 => Some(Self::Kbps32),
+            40 => Some(Self::Kbps40),
+            48 => Some(Self::Kbps48),
+            56 => Some(Self::Kbps56),
+            64 => Some(Self::Kbps64),
+            80 => Some(Self::Kbps80),
+            96 => Some(Self::Kbps96),
+            112 => Some(Self::Kbps112),
+            128 => Some(Self::Kbps128),
+            144 => Some(Self::Kbps144),
+            160 => Some(Self::Kbps160),
+            192 => Some(Self::Kbps192),
+            224 => Some(Self::Kbps224),
+            256 => Some(Self::Kbps256),
+            320 => Some(Self::Kbps320),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    ///Returns whether bitrate can be used as frame bitrate for MPEG `version`
+    pub fn is_allowed_for(self, version: MpegVersion) -> bool {
+        version.bitrates().contains(&(self as u16))
+    }
+}
+
 ///Possible quality parameter.
 ///From best(0) to worst(9)
-#[derive(Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Quality {
     ///Best possible quality
@@ -334,6 +624,160 @@ pub enum Quality {
     Worst = 9,
 }
 
+impl Quality {
+    #[inline(always)]
+    fn from_raw(quality: c_int) -> Self {
+        match quality {
+            quality if quality <= 0 => Self::Best,
+            1 => Self::SecondBest,
+            2 => Self::NearBest,
+            3 => Self::VeryNice,
+            4 => Self::Nice,
+            5 => Self::Good,
+            6 => Self::Decent,
+            7 => Self::Ok,
+            8 => Self::SecondWorst,
+            _ => Self::Worst,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///LAME presets, equivalent to `lame` command line `-V` and `--preset` options.
+pub enum Preset {
+    ///VBR preset of best quality (`-V 0`)
+    V0,
+    ///VBR preset (`-V 1`)
+    V1,
+    ///VBR preset (`-V 2`)
+    V2,
+    ///VBR preset (`-V 3`)
+    V3,
+    ///VBR preset (`-V 4`)
+    V4,
+    ///VBR preset (`-V 5`)
+    V5,
+    ///VBR preset (`-V 6`)
+    V6,
+    ///VBR preset (`-V 7`)
+    V7,
+    ///VBR preset (`-V 8`)
+    V8,
+    ///VBR preset of worst quality (`-V 9`)
+    V9,
+    ///`--preset medium`, equivalent to `-V 4`
+    Medium,
+    ///`--preset standard`, equivalent to `-V 2`
+    Standard,
+    ///`--preset extreme`, equivalent to `-V 0`
+    Extreme,
+    ///`--preset insane`, equivalent to CBR 320kbps
+    Insane,
+    ///ABR preset with specified target bitrate (`--preset <bitrate>`)
+    Abr(Bitrate),
+}
+
+impl Preset {
+    #[inline]
+    //Values of `preset_mode` enum
+    const fn to_raw(self) -> c_int {
+        match self {
+            Self::V0 => 500,
+            Self::V1 => 490,
+            Self::V2 => 480,
+            Self::V3 => 470,
+            Self::V4 => 460,
+            Self::V5 => 450,
+            Self::V6 => 440,
+            Self::V7 => 430,
+            Self::V8 => 420,
+            Self::V9 => 410,
+            Self::Standard => 1001,
+            Self::Extreme => 1002,
+            Self::Insane => 1003,
+            Self::Medium => 1006,
+            Self::Abr(bitrate) => bitrate as c_int,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///Lowpass or highpass filter setting
+pub enum Filter {
+    ///Filter is not applied
+    Disabled,
+    ///Cutoff frequency (Hz)
+    Frequency(NonZeroU32),
+}
+
+impl Filter {
+    #[inline]
+    //LAME uses 0 to pick default and negative value to disable
+    fn to_raw(filter: Option<Self>) -> c_int {
+        match filter {
+            None => 0,
+            Some(Self::Disabled) => -1,
+            Some(Self::Frequency(freq)) => freq.get().try_into().unwrap_or(c_int::MAX),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+///Gain applied to input samples
+pub enum Gain {
+    ///Linear multiplier, where `1.0` leaves samples unchanged
+    Linear(f32),
+    ///Decibels, where `0.0` leaves samples unchanged
+    Db(f32),
+}
+
+impl Gain {
+    ///Returns gain as linear multiplier.
+    ///
+    ///Returns `None` if gain is not finite or linear gain is negative.
+    pub fn to_linear(self) -> Option<f32> {
+        let scale = match self {
+            Self::Linear(scale) => scale,
+            Self::Db(db) if db.is_finite() => exp(db as f64 * core::f64::consts::LN_10 / 20.0) as f32,
+            Self::Db(_) => return None,
+        };
+
+        if scale.is_finite() && scale >= 0.0 {
+            Some(scale)
+        } else {
+            None
+        }
+    }
+}
+
+//core lacks float functions, but precision of gain doesn't need to be great
+fn exp(x: f64) -> f64 {
+    use core::f64::consts::LN_2;
+
+    if x > 709.0 {
+        return f64::INFINITY;
+    } else if x < -708.0 {
+        return 0.0;
+    }
+
+    //x = k * ln(2) + r, where |r| <= ln(2)
+    let k = (x / LN_2) as i64;
+    let r = x - k as f64 * LN_2;
+
+    let mut result = 1.0;
+    let mut term = 1.0;
+    for idx in 1..=20 {
+        term *= r / idx as f64;
+        result += term;
+    }
+
+    //2^k is within range of normal numbers, so can be constructed directly
+    result * f64::from_bits(((k + 1023) as u64) << 52)
+}
+
 #[derive(Copy, Clone)]
 ///ID3 tag as raw bytes.
 ///
@@ -514,178 +958,822 @@ impl Builder {
     }
 
     #[inline]
-    ///Sets MPEG mode.
+    //Returns MPEG version alongside output sample rate, if it is set
+    fn output_mpeg_version(&mut self) -> Result<Option<(MpegVersion, u32)>, BuildError> {
+        let rate = unsafe {
+            ffi::lame_get_out_samplerate(self.ptr())
+        };
+        match rate {
+            0 => Ok(None),
+            rate => match MpegVersion::from_sample_rate(rate as _) {
+                Some(version) => Ok(Some((version, rate as _))),
+                None => Err(BuildError::UnsupportedSampleRate(rate as _)),
+            },
+        }
+    }
+
+    #[inline]
+    ///Sets ABR target bitrate (as kbps).
     ///
-    ///Default is picked by LAME depending on compression ration and input channels.
+    ///Only used with [VbrMode::Abr].
+    ///
+    ///If output sample rate is set, bitrate must be within range allowed for its MPEG version.
     ///
     ///Returns whether it is supported or not.
-    pub fn set_mode(&mut self, mode: Mode) -> Result<(), BuildError> {
+    pub fn set_vbr_mean_bitrate(&mut self, brate: Bitrate) -> Result<(), BuildError> {
+        if let Some((version, sample_rate)) = self.output_mpeg_version()? {
+            version.check_bitrate_range(sample_rate, brate as _)?;
+        }
+
         let res = unsafe {
-            ffi::lame_set_mode(self.ptr(), mode as _)
+            ffi::lame_set_VBR_mean_bitrate_kbps(self.ptr(), brate as _)
         };
 
         BuildError::from_c_int(res)
     }
 
     #[inline]
-    ///Sets MPEG mode using the builder pattern.
+    ///Sets ABR target bitrate (as kbps) using the builder pattern.
     ///
-    ///Default is picked by LAME depending on compression ration and input channels.
+    ///Only used with [VbrMode::Abr].
     ///
     ///Returns an error if it is not supported.
-    pub fn with_mode(mut self, mode: Mode) -> Result<Self, BuildError> {
-        self.set_mode(mode)?;
+    pub fn with_vbr_mean_bitrate(mut self, brate: Bitrate) -> Result<Self, BuildError> {
+        self.set_vbr_mean_bitrate(brate)?;
         Ok(self)
     }
 
     #[inline]
-    ///Sets quality.
+    ///Sets minimum VBR/ABR bitrate (as kbps).
     ///
-    ///Default is good one(5)
+    ///If output sample rate is set, bitrate must be allowed for its MPEG version.
+    ///Must not be greater than maximum bitrate, if it is set.
     ///
     ///Returns whether it is supported or not.
-    pub fn set_quality(&mut self, quality: Quality) -> Result<(), BuildError> {
+    pub fn set_vbr_min_bitrate(&mut self, brate: Bitrate) -> Result<(), BuildError> {
+        if let Some((version, sample_rate)) = self.output_mpeg_version()? {
+            version.check_bitrate(sample_rate, brate as _)?;
+        }
+
+        let max = unsafe {
+            ffi::lame_get_VBR_max_bitrate_kbps(self.ptr())
+        };
+        if max > 0 && (brate as c_int) > max {
+            return Err(BuildError::BadBRate);
+        }
+
         let res = unsafe {
-            ffi::lame_set_quality(self.ptr(), quality as _)
+            ffi::lame_set_VBR_min_bitrate_kbps(self.ptr(), brate as _)
         };
 
         BuildError::from_c_int(res)
     }
 
     #[inline]
-    ///Sets quality using the builder pattern.
-    ///
-    ///Default is good one(5)
+    ///Sets minimum VBR/ABR bitrate (as kbps) using the builder pattern.
     ///
     ///Returns an error if it is not supported.
-    pub fn with_quality(mut self, quality: Quality) -> Result<Self, BuildError> {
-        self.set_quality(quality)?;
+    pub fn with_vbr_min_bitrate(mut self, brate: Bitrate) -> Result<Self, BuildError> {
+        self.set_vbr_min_bitrate(brate)?;
         Ok(self)
     }
 
     #[inline]
-    ///Sets VBR quality.
+    ///Sets maximum VBR/ABR bitrate (as kbps).
+    ///
+    ///If output sample rate is set, bitrate must be allowed for its MPEG version.
+    ///Must not be less than minimum bitrate, if it is set.
     ///
     ///Returns whether it is supported or not.
-    pub fn set_vbr_quality(&mut self, quality: Quality) -> Result<(), BuildError> {
+    pub fn set_vbr_max_bitrate(&mut self, brate: Bitrate) -> Result<(), BuildError> {
+        if let Some((version, sample_rate)) = self.output_mpeg_version()? {
+            version.check_bitrate(sample_rate, brate as _)?;
+        }
+
+        let min = unsafe {
+            ffi::lame_get_VBR_min_bitrate_kbps(self.ptr())
+        };
+        if min > 0 && (brate as c_int) < min {
+            return Err(BuildError::BadBRate);
+        }
+
         let res = unsafe {
-            ffi::lame_set_VBR_q(self.ptr(), quality as _)
+            ffi::lame_set_VBR_max_bitrate_kbps(self.ptr(), brate as _)
         };
 
         BuildError::from_c_int(res)
     }
 
     #[inline]
-    ///Sets VBR quality using the builder pattern.
+    ///Sets maximum VBR/ABR bitrate (as kbps) using the builder pattern.
     ///
     ///Returns an error if it is not supported.
-    pub fn with_vbr_quality(mut self, quality: Quality) -> Result<Self, BuildError> {
-        self.set_vbr_quality(quality)?;
+    pub fn with_vbr_max_bitrate(mut self, brate: Bitrate) -> Result<Self, BuildError> {
+        self.set_vbr_max_bitrate(brate)?;
         Ok(self)
     }
 
     #[inline]
-    ///Sets whether to write VBR tag.
+    ///Sets whether minimum VBR bitrate is strictly enforced.
     ///
-    ///Default is true.
+    ///Normally it is only enforced for non-silent frames, while silence is encoded with lowest possible bitrate.
+    ///
+    ///Default is false.
     ///
     ///Returns whether it is supported or not.
-    pub fn set_to_write_vbr_tag(&mut self, value: bool) -> Result<(), BuildError> {
+    pub fn set_vbr_hard_min(&mut self, value: bool) -> Result<(), BuildError> {
         let res = unsafe {
-            ffi::lame_set_bWriteVbrTag(self.ptr(), value as _)
+            ffi::lame_set_VBR_hard_min(self.ptr(), value as _)
         };
 
         BuildError::from_c_int(res)
     }
 
     #[inline]
-    ///Sets whether to write VBR tag using the builder pattern.
+    ///Sets whether minimum VBR bitrate is strictly enforced using the builder pattern.
     ///
-    ///Default is true.
+    ///Default is false.
     ///
     ///Returns an error if it is not supported.
-    pub fn with_to_write_vbr_tag(mut self, value: bool) -> Result<Self, BuildError> {
-        self.set_to_write_vbr_tag(value)?;
+    pub fn with_vbr_hard_min(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_vbr_hard_min(value)?;
         Ok(self)
     }
 
     #[inline]
-    ///Sets VBR mode.
+    //Returns sample rate that is going to be used for encoding, if it is known
+    fn encoding_sample_rate(&mut self) -> u32 {
+        let rate = unsafe {
+            match ffi::lame_get_out_samplerate(self.ptr()) {
+                0 => ffi::lame_get_in_samplerate(self.ptr()),
+                rate => rate,
+            }
+        };
+        rate as _
+    }
+
+    //Checks that frequency is below Nyquist frequency
+    fn check_filter_freq(&mut self, frequency: c_int) -> Result<(), BuildError> {
+        let sample_rate = self.encoding_sample_rate();
+        if frequency > 0 && (frequency as u32).saturating_mul(2) >= sample_rate {
+            Err(BuildError::FilterAboveNyquist {
+                frequency: frequency as _,
+                sample_rate,
+            })
+        } else {
+            Ok(())
+        }
+    }
+
+    #[inline]
+    ///Sets lowpass filter.
     ///
-    ///Default is off (i.e. CBR)
+    ///The default `None` allows LAME to pick frequency suitable for bitrate.
+    ///
+    ///Frequency must be below Nyquist frequency of output sample rate (or input sample rate, if output is not set).
     ///
     ///Returns whether it is supported or not.
-    pub fn set_vbr_mode(&mut self, value: VbrMode) -> Result<(), BuildError> {
+    pub fn set_lowpass(&mut self, filter: Option<Filter>) -> Result<(), BuildError> {
+        let freq = Filter::to_raw(filter);
+        self.check_filter_freq(freq)?;
+
         let res = unsafe {
-            ffi::lame_set_VBR(self.ptr(), value as _)
+            ffi::lame_set_lowpassfreq(self.ptr(), freq)
         };
 
         BuildError::from_c_int(res)
     }
 
     #[inline]
-    ///Sets VBR mode using the bulder pattern.
+    ///Sets lowpass filter using the builder pattern.
     ///
-    ///Default is off (i.e. CBR)
+    ///The default `None` allows LAME to pick frequency suitable for bitrate.
     ///
     ///Returns an error if it is not supported.
-    pub fn with_vbr_mode(mut self, value: VbrMode) -> Result<Self, BuildError> {
-        self.set_vbr_mode(value)?;
+    pub fn with_lowpass(mut self, filter: Option<Filter>) -> Result<Self, BuildError> {
+        self.set_lowpass(filter)?;
         Ok(self)
     }
 
     #[inline]
-    ///Sets id3tag tag.
+    ///Sets width of lowpass filter transition band (Hz).
     ///
-    ///If [FlushGap] is used, then `v1` will not be added.
-    ///But `v2` is always added at the beginning.
+    ///The default `None` allows LAME to pick width.
     ///
     ///Returns whether it is supported or not.
-    pub fn set_id3_tag(&mut self, value: Id3Tag<'_>) -> Result<(), Id3TagError> {
-        if !value.is_any_set() {
-            return Ok(());
-        }
+    pub fn set_lowpass_width(&mut self, width: Option<NonZeroU32>) -> Result<(), BuildError> {
+        let width = width.map_or(-1, |width| width.get().try_into().unwrap_or(c_int::MAX));
 
-        const MAX_BUFFER: usize = 250;
-        let mut buffer = [0u8; MAX_BUFFER + 1];
+        let res = unsafe {
+            ffi::lame_set_lowpasswidth(self.ptr(), width)
+        };
 
-        unsafe {
-            ffi::id3tag_init(self.ptr());
-            ffi::id3tag_add_v2(self.ptr());
+        BuildError::from_c_int(res)
+    }
 
-            if !value.album_art.is_empty() {
-                let size = value.album_art.len();
-                if size > MAX_ALBUM_ART_SIZE {
-                    return Err(Id3TagError::AlbumArtOverflow);
-                }
-                let ptr = value.album_art.as_ptr();
-                ffi::id3tag_set_albumart(self.ptr(), ptr as _, size);
-            }
+    #[inline]
+    ///Sets width of lowpass filter transition band (Hz) using the builder pattern.
+    ///
+    ///The default `None` allows LAME to pick width.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_lowpass_width(mut self, width: Option<NonZeroU32>) -> Result<Self, BuildError> {
+        self.set_lowpass_width(width)?;
+        Ok(self)
+    }
 
-            if !value.title.is_empty() {
-                let size = cmp::min(MAX_BUFFER, value.title.len());
-                ptr::copy_nonoverlapping(value.title.as_ptr(), buffer.as_mut_ptr(), size);
-                buffer[size] = 0;
-                ffi::id3tag_set_title(self.ptr(), buffer.as_ptr() as _);
-            }
+    #[inline]
+    ///Sets highpass filter.
+    ///
+    ///The default `None` allows LAME to decide, which normally means no filter.
+    ///
+    ///Frequency must be below Nyquist frequency of output sample rate (or input sample rate, if output is not set).
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_highpass(&mut self, filter: Option<Filter>) -> Result<(), BuildError> {
+        let freq = Filter::to_raw(filter);
+        self.check_filter_freq(freq)?;
 
-            if !value.album.is_empty() {
-                let size = cmp::min(MAX_BUFFER, value.album.len());
-                ptr::copy_nonoverlapping(value.album.as_ptr(), buffer.as_mut_ptr(), size);
-                buffer[size] = 0;
-                ffi::id3tag_set_album(self.ptr(), buffer.as_ptr() as _);
-            }
+        let res = unsafe {
+            ffi::lame_set_highpassfreq(self.ptr(), freq)
+        };
 
-            if !value.artist.is_empty() {
-                let size = cmp::min(MAX_BUFFER, value.artist.len());
-                ptr::copy_nonoverlapping(value.artist.as_ptr(), buffer.as_mut_ptr(), size);
-                buffer[size] = 0;
-                ffi::id3tag_set_artist(self.ptr(), buffer.as_ptr() as _);
-            }
+        BuildError::from_c_int(res)
+    }
 
-            if !value.year.is_empty() {
-                let size = cmp::min(MAX_BUFFER, value.year.len());
-                ptr::copy_nonoverlapping(value.year.as_ptr(), buffer.as_mut_ptr(), size);
+    #[inline]
+    ///Sets highpass filter using the builder pattern.
+    ///
+    ///The default `None` allows LAME to decide, which normally means no filter.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_highpass(mut self, filter: Option<Filter>) -> Result<Self, BuildError> {
+        self.set_highpass(filter)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets width of highpass filter transition band (Hz).
+    ///
+    ///The default `None` allows LAME to pick width.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_highpass_width(&mut self, width: Option<NonZeroU32>) -> Result<(), BuildError> {
+        let width = width.map_or(-1, |width| width.get().try_into().unwrap_or(c_int::MAX));
+
+        let res = unsafe {
+            ffi::lame_set_highpasswidth(self.ptr(), width)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets width of highpass filter transition band (Hz) using the builder pattern.
+    ///
+    ///The default `None` allows LAME to pick width.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_highpass_width(mut self, width: Option<NonZeroU32>) -> Result<Self, BuildError> {
+        self.set_highpass_width(width)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets gain, applied to all input samples.
+    ///
+    ///Default is `Gain::Linear(1.0)`
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_scale(&mut self, gain: Gain) -> Result<(), BuildError> {
+        let scale = gain.to_linear().ok_or(BuildError::InvalidGain)?;
+
+        let res = unsafe {
+            ffi::lame_set_scale(self.ptr(), scale)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets gain, applied to all input samples, using the builder pattern.
+    ///
+    ///Default is `Gain::Linear(1.0)`
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_scale(mut self, gain: Gain) -> Result<Self, BuildError> {
+        self.set_scale(gain)?;
+        Ok(self)
+    }
+
+    ///Sets gain of each input channel, applied in addition to [scale](Self::set_scale).
+    ///
+    ///Default is `Gain::Linear(1.0)` for both channels.
+    ///`right` is ignored for mono input.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_channel_scale(&mut self, left: Gain, right: Gain) -> Result<(), BuildError> {
+        let left = left.to_linear().ok_or(BuildError::InvalidGain)?;
+        let right = right.to_linear().ok_or(BuildError::InvalidGain)?;
+
+        let res = unsafe {
+            ffi::lame_set_scale_left(self.ptr(), left)
+        };
+        BuildError::from_c_int(res)?;
+
+        let res = unsafe {
+            ffi::lame_set_scale_right(self.ptr(), right)
+        };
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets gain of each input channel using the builder pattern.
+    ///
+    ///Default is `Gain::Linear(1.0)` for both channels.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_channel_scale(mut self, left: Gain, right: Gain) -> Result<Self, BuildError> {
+        self.set_channel_scale(left, right)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets MPEG mode.
+    ///
+    ///Default is picked by LAME depending on compression ration and input channels.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_mode(&mut self, mode: Mode) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_mode(self.ptr(), mode as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets MPEG mode using the builder pattern.
+    ///
+    ///Default is picked by LAME depending on compression ration and input channels.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_mode(mut self, mode: Mode) -> Result<Self, BuildError> {
+        self.set_mode(mode)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets copyright bit of frame header.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_copyright(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_copyright(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets copyright bit of frame header using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_copyright(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_copyright(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets original bit of frame header.
+    ///
+    ///Default is true.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_original(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_original(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets original bit of frame header using the builder pattern.
+    ///
+    ///Default is true.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_original(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_original(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets private extension bit of frame header.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_extension(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_extension(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets private extension bit of frame header using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_extension(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_extension(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to protect frames by CRC.
+    ///
+    ///It costs 2 bytes of every frame.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_crc(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_error_protection(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to protect frames by CRC using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_crc(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_crc(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets emphasis of frame header.
+    ///
+    ///Default is `Emphasis::None`.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_emphasis(&mut self, emphasis: Emphasis) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_emphasis(self.ptr(), emphasis as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets emphasis of frame header using the builder pattern.
+    ///
+    ///Default is `Emphasis::None`.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_emphasis(mut self, emphasis: Emphasis) -> Result<Self, BuildError> {
+        self.set_emphasis(emphasis)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to use free format bitrate.
+    ///
+    ///Frames of free format stream do not specify bitrate, allowing to use bitrate not listed by MPEG standard,
+    ///but not every decoder supports it.
+    ///Only applicable to CBR.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_free_format(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_free_format(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to use free format bitrate using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_free_format(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_free_format(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to enforce strict ISO compliance.
+    ///
+    ///When enabled, frame buffer is limited to size mandated by ISO.
+    ///Otherwise LAME's default maximum frame buffer is used.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_strict_iso(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_strict_ISO(self.ptr(), match value {
+                true => MDB_STRICT_ISO,
+                false => MDB_MAXIMUM,
+            })
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to enforce strict ISO compliance using the builder pattern.
+    ///
+    ///Refer to [set_strict_iso](Self::set_strict_iso) for details.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_strict_iso(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_strict_iso(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to disable bit reservoir.
+    ///
+    ///Without bit reservoir, every frame can be decoded independently, at cost of quality.
+    ///This makes every frame a safe point to cut or splice stream (see [Mp3Frame::is_splice_point]).
+    ///
+    ///LAME cannot limit it to particular frames, hence bit reservoir is either used or disabled for the whole stream.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_disable_reservoir(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_disable_reservoir(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to disable bit reservoir using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_disable_reservoir(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_disable_reservoir(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets quality.
+    ///
+    ///Default is good one(5)
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_quality(&mut self, quality: Quality) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_quality(self.ptr(), quality as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets quality using the builder pattern.
+    ///
+    ///Default is good one(5)
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_quality(mut self, quality: Quality) -> Result<Self, BuildError> {
+        self.set_quality(quality)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets VBR quality.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_vbr_quality(&mut self, quality: Quality) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_VBR_q(self.ptr(), quality as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets VBR quality using the builder pattern.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_vbr_quality(mut self, quality: Quality) -> Result<Self, BuildError> {
+        self.set_vbr_quality(quality)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to write VBR tag.
+    ///
+    ///Default is true.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_to_write_vbr_tag(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_bWriteVbrTag(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to write VBR tag using the builder pattern.
+    ///
+    ///Default is true.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_to_write_vbr_tag(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_to_write_vbr_tag(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to perform ReplayGain analysis of input.
+    ///
+    ///Result is available via [Encoder::gain_analysis] and is written into LAME tag.
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_find_replay_gain(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_findReplayGain(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to perform ReplayGain analysis of input using the builder pattern.
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_find_replay_gain(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_find_replay_gain(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets whether to decode output on the fly, in order to find peak sample.
+    ///
+    ///Result is available via [Encoder::gain_analysis] and is written into LAME tag.
+    ///
+    ///Requires LAME to be built with decoder (i.e. `decoder` feature).
+    ///
+    ///Default is false.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_decode_on_the_fly(&mut self, value: bool) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_decode_on_the_fly(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets whether to decode output on the fly using the builder pattern.
+    ///
+    ///Requires LAME to be built with decoder (i.e. `decoder` feature).
+    ///
+    ///Default is false.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_decode_on_the_fly(mut self, value: bool) -> Result<Self, BuildError> {
+        self.set_decode_on_the_fly(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets preset.
+    ///
+    ///Preset configures VBR mode, bitrate, quality and various internal parameters in the same way as `lame` command line.
+    ///Therefore it overrides previously set values, so any further adjustments should be done after setting preset.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_preset(&mut self, preset: Preset) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_preset(self.ptr(), preset.to_raw())
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets preset using the builder pattern.
+    ///
+    ///Preset overrides previously set values, so any further adjustments should be done after setting preset.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_preset(mut self, preset: Preset) -> Result<Self, BuildError> {
+        self.set_preset(preset)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets VBR mode.
+    ///
+    ///Default is off (i.e. CBR)
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_vbr_mode(&mut self, value: VbrMode) -> Result<(), BuildError> {
+        let res = unsafe {
+            ffi::lame_set_VBR(self.ptr(), value as _)
+        };
+
+        BuildError::from_c_int(res)
+    }
+
+    #[inline]
+    ///Sets VBR mode using the bulder pattern.
+    ///
+    ///Default is off (i.e. CBR)
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_vbr_mode(mut self, value: VbrMode) -> Result<Self, BuildError> {
+        self.set_vbr_mode(value)?;
+        Ok(self)
+    }
+
+    #[inline]
+    ///Sets id3tag tag.
+    ///
+    ///If [FlushGap] is used, then `v1` will not be added.
+    ///But `v2` is always added at the beginning.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_id3_tag(&mut self, value: Id3Tag<'_>) -> Result<(), Id3TagError> {
+        if !value.is_any_set() {
+            return Ok(());
+        }
+
+        const MAX_BUFFER: usize = 250;
+        let mut buffer = [0u8; MAX_BUFFER + 1];
+
+        unsafe {
+            ffi::id3tag_init(self.ptr());
+            ffi::id3tag_add_v2(self.ptr());
+
+            if !value.album_art.is_empty() {
+                let size = value.album_art.len();
+                if size > MAX_ALBUM_ART_SIZE {
+                    return Err(Id3TagError::AlbumArtOverflow);
+                }
+                let ptr = value.album_art.as_ptr();
+                ffi::id3tag_set_albumart(self.ptr(), ptr as _, size);
+            }
+
+            if !value.title.is_empty() {
+                let size = cmp::min(MAX_BUFFER, value.title.len());
+                ptr::copy_nonoverlapping(value.title.as_ptr(), buffer.as_mut_ptr(), size);
+                buffer[size] = 0;
+                ffi::id3tag_set_title(self.ptr(), buffer.as_ptr() as _);
+            }
+
+            if !value.album.is_empty() {
+                let size = cmp::min(MAX_BUFFER, value.album.len());
+                ptr::copy_nonoverlapping(value.album.as_ptr(), buffer.as_mut_ptr(), size);
+                buffer[size] = 0;
+                ffi::id3tag_set_album(self.ptr(), buffer.as_ptr() as _);
+            }
+
+            if !value.artist.is_empty() {
+                let size = cmp::min(MAX_BUFFER, value.artist.len());
+                ptr::copy_nonoverlapping(value.artist.as_ptr(), buffer.as_mut_ptr(), size);
+                buffer[size] = 0;
+                ffi::id3tag_set_artist(self.ptr(), buffer.as_ptr() as _);
+            }
+
+            if !value.year.is_empty() {
+                let size = cmp::min(MAX_BUFFER, value.year.len());
+                ptr::copy_nonoverlapping(value.year.as_ptr(), buffer.as_mut_ptr(), size);
                 buffer[size] = 0;
                 ffi::id3tag_set_year(self.ptr(), buffer.as_ptr() as _);
             }
@@ -712,11 +1800,139 @@ impl Builder {
         Ok(self)
     }
 
+    #[inline]
+    ///Sets id3tag tag using [Id3TagBuilder].
+    ///
+    ///Unlike [Builder::set_id3_tag], text is not truncated and non Latin-1 text is written as UTF-16.
+    ///
+    ///Returns whether it is supported or not.
+    pub fn set_id3_tag_builder(&mut self, value: &Id3TagBuilder<'_>) -> Result<(), Id3TagError> {
+        if !value.is_any_set() {
+            return Ok(());
+        }
+
+        value.apply(self.ptr())
+    }
+
+    #[inline]
+    ///Sets id3tag tag using [Id3TagBuilder] and the builder pattern.
+    ///
+    ///Returns an error if it is not supported.
+    pub fn with_id3_tag_builder(mut self, value: &Id3TagBuilder<'_>) -> Result<Self, Id3TagError> {
+        self.set_id3_tag_builder(value)?;
+        Ok(self)
+    }
+
+    ///Validates parameters against sample rate.
+    ///
+    ///Filter frequencies are checked against Nyquist frequency.
+    ///
+    ///Bitrates are checked against MPEG version of output sample rate.
+    ///Only performed if output sample rate is set via [Builder::set_output_sample_rate],
+    ///otherwise LAME picks sample rate suitable for bitrate.
+    ///It covers constant bitrate (unless VBR or free format is used), ABR target bitrate and VBR bitrate bounds.
+    ///
+    ///Free format is rejected with VBR, as LAME would silently ignore it.
+    ///
+    ///Called by [Builder::build], but can be used to report problem before that.
+    pub fn validate(&mut self) -> Result<(), BuildError> {
+        let (lowpass, highpass) = unsafe {
+            (ffi::lame_get_lowpassfreq(self.ptr()), ffi::lame_get_highpassfreq(self.ptr()))
+        };
+        self.check_filter_freq(lowpass)?;
+        self.check_filter_freq(highpass)?;
+
+        let (vbr, free_format) = unsafe {
+            (ffi::lame_get_VBR(self.ptr()), ffi::lame_get_free_format(self.ptr()) != 0)
+        };
+        if free_format && vbr != ffi::vbr_mode::vbr_off {
+            return Err(BuildError::IncompatibleOptions {
+                option: "free format",
+                conflict: "VBR",
+            });
+        }
+
+        let (version, sample_rate) = match self.output_mpeg_version()? {
+            Some(output) => output,
+            None => return Ok(()),
+        };
+
+        let (brate, mean, min, max) = unsafe {
+            let ptr = self.ptr();
+            (
+                ffi::lame_get_brate(ptr),
+                ffi::lame_get_VBR_mean_bitrate_kbps(ptr),
+                ffi::lame_get_VBR_min_bitrate_kbps(ptr),
+                ffi::lame_get_VBR_max_bitrate_kbps(ptr),
+            )
+        };
+
+        if vbr == ffi::vbr_mode::vbr_off {
+            //Free format allows any bitrate
+            if brate > 0 && !free_format {
+                version.check_bitrate(sample_rate, brate)?;
+            }
+        } else {
+            if vbr == ffi::vbr_mode::vbr_abr {
+                version.check_bitrate_range(sample_rate, mean)?;
+            }
+            if min > 0 {
+                version.check_bitrate(sample_rate, min)?;
+            }
+            if max > 0 {
+                version.check_bitrate(sample_rate, max)?;
+            }
+        }
+
+        Ok(())
+    }
+
     #[inline]
     ///Attempts to initialize encoder with specified parameters.
     ///
+    ///Parameters are checked using [Builder::validate] first.
+    ///
     ///Returns `None` if parameters are invalid or incompatible.
-    pub fn build(mut self) -> Result<Encoder, BuildError> {
+    pub fn build(self) -> Result<Encoder, BuildError> {
+        self.build_typed()
+    }
+
+    ///Attempts to initialize encoder with number of channels known at compile time.
+    ///
+    ///Number of channels is set according to `C` (unless it is [Dynamic]),
+    ///so that resulting encoder only accepts input of matching shape.
+    ///
+    ///Refer for details to `build()`
+    ///
+    ///## Example
+    ///
+    ///```rust
+    ///use mp3lame_encoder::{Builder, Mono, MonoPcm};
+    ///
+    ///let mut mp3_encoder = Builder::new().expect("Create LAME builder").build_typed::<Mono>().expect("To initialize LAME encoder");
+    ///assert_eq!(mp3_encoder.num_channels(), 1);
+    ///
+    ///let mut mp3_out_buffer = Vec::new();
+    ///mp3_encoder.encode_into(MonoPcm(&[0i16; 1152][..]), &mut mp3_out_buffer).expect("To encode");
+    ///```
+    ///
+    ///Passing input of other shape doesn't compile:
+    ///
+    ///```rust,compile_fail
+    ///use mp3lame_encoder::{Builder, Mono, DualPcm};
+    ///
+    ///let mut mp3_encoder = Builder::new().expect("Create LAME builder").build_typed::<Mono>().expect("To initialize LAME encoder");
+    ///
+    ///let mut mp3_out_buffer = Vec::new();
+    ///mp3_encoder.encode_into(DualPcm { left: &[0i16; 1152][..], right: &[0i16; 1152][..] }, &mut mp3_out_buffer).expect("To encode");
+    ///```
+    pub fn build_typed<C: Channels>(mut self) -> Result<Encoder<C>, BuildError> {
+        if let Some(num_channels) = C::NUM_CHANNELS {
+            self.set_num_channels(num_channels)?;
+        }
+
+        self.validate()?;
+
         let res = unsafe {
             ffi::lame_init_params(self.ptr())
         };
@@ -725,7 +1941,10 @@ impl Builder {
             Ok(()) => {
                 let inner = self.inner;
                 mem::forget(self);
-                Ok(Encoder { inner })
+                Ok(Encoder {
+                    inner,
+                    _channels: PhantomData,
+                })
             },
             Err(error) => Err(error),
         }
@@ -741,17 +1960,62 @@ impl Drop for Builder {
     }
 }
 
+#[derive(Debug, Copy, Clone, PartialEq)]
+///Results of gain analysis, performed by encoder.
+pub struct GainAnalysis {
+    ///ReplayGain radio (track) gain adjustment in dB.
+    ///
+    ///Available if [Builder::set_find_replay_gain] is enabled.
+    pub radio_gain_db: Option<f32>,
+    ///Peak sample in 16-bit sample scale (i.e. `32767.0` is full scale).
+    ///
+    ///Available if [Builder::set_decode_on_the_fly] is enabled.
+    pub peak_sample: Option<f32>,
+    ///Gain change in dB required to prevent clipping.
+    ///
+    ///Available if [Builder::set_decode_on_the_fly] is enabled.
+    pub noclip_gain_change_db: Option<f32>,
+    ///Input scale factor required to prevent clipping.
+    ///
+    ///Available if [Builder::set_decode_on_the_fly] is enabled and LAME is able to determine it.
+    pub noclip_scale: Option<f32>,
+}
+
 ///LAME Encoder.
-pub struct Encoder {
+///
+///`C` determines shape of input that can be encoded (see [Channels]).
+///By default number of channels is only known at runtime.
+#[repr(transparent)]
+pub struct Encoder<C = Dynamic> {
     inner: NonNull<ffi::lame_global_flags>,
+    _channels: PhantomData<C>,
 }
 
-impl Encoder {
+impl<C: Channels> Encoder<C> {
     #[inline(always)]
     fn ptr(&self) -> *mut ffi::lame_global_flags {
         self.inner.as_ptr()
     }
 
+    #[inline(always)]
+    fn as_dynamic(&mut self) -> &mut Encoder {
+        //Safe as Encoder is transparent over pointer regardless of `C`
+        unsafe {
+            &mut *(self as *mut Self as *mut Encoder)
+        }
+    }
+
+    #[inline]
+    ///Converts into encoder, accepting any input.
+    pub fn into_dynamic(self) -> Encoder {
+        let inner = self.inner;
+        mem::forget(self);
+        Encoder {
+            inner,
+            _channels: PhantomData,
+        }
+    }
+
     #[inline]
     ///Returns sample rate.
     pub fn sample_rate(&self) -> u32 {
@@ -768,6 +2032,151 @@ impl Encoder {
         }
     }
 
+    #[inline]
+    ///Returns lowpass filter frequency chosen by LAME.
+    ///
+    ///Returns `None` if filter is disabled.
+    pub fn lowpass_freq(&self) -> Option<u32> {
+        let freq = unsafe {
+            ffi::lame_get_lowpassfreq(self.ptr())
+        };
+        u32::try_from(freq).ok().filter(|freq| *freq > 0)
+    }
+
+    #[inline]
+    ///Returns highpass filter frequency chosen by LAME.
+    ///
+    ///Returns `None` if filter is disabled.
+    pub fn highpass_freq(&self) -> Option<u32> {
+        let freq = unsafe {
+            ffi::lame_get_highpassfreq(self.ptr())
+        };
+        u32::try_from(freq).ok().filter(|freq| *freq > 0)
+    }
+
+    #[inline]
+    ///Returns linear gain, applied to all input samples.
+    ///
+    ///This is gain used by LAME, rather than one set via [Builder::set_scale].
+    ///For CBR and ABR, LAME multiplies it by bitrate dependent factor (below 1.0) to reduce clipping.
+    pub fn scale(&self) -> f32 {
+        unsafe {
+            ffi::lame_get_scale(self.ptr())
+        }
+    }
+
+    #[inline]
+    ///Returns linear gain of left and right channels, applied in addition to [scale](Self::scale).
+    pub fn channel_scale(&self) -> (f32, f32) {
+        unsafe {
+            (ffi::lame_get_scale_left(self.ptr()), ffi::lame_get_scale_right(self.ptr()))
+        }
+    }
+
+    #[inline]
+    ///Returns whether frames are marked as copyrighted
+    pub fn is_copyright(&self) -> bool {
+        unsafe {
+            ffi::lame_get_copyright(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether frames are marked as original
+    pub fn is_original(&self) -> bool {
+        unsafe {
+            ffi::lame_get_original(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether private extension bit of frames is set
+    pub fn is_extension(&self) -> bool {
+        unsafe {
+            ffi::lame_get_extension(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether frames are protected by CRC
+    pub fn is_crc(&self) -> bool {
+        unsafe {
+            ffi::lame_get_error_protection(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether free format bitrate is used
+    pub fn is_free_format(&self) -> bool {
+        unsafe {
+            ffi::lame_get_free_format(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns whether strict ISO compliance is enforced
+    pub fn is_strict_iso(&self) -> bool {
+        unsafe {
+            ffi::lame_get_strict_ISO(self.ptr()) == MDB_STRICT_ISO
+        }
+    }
+
+    #[inline]
+    ///Returns whether bit reservoir is disabled
+    pub fn is_reservoir_disabled(&self) -> bool {
+        unsafe {
+            ffi::lame_get_disable_reservoir(self.ptr()) != 0
+        }
+    }
+
+    #[inline]
+    ///Returns emphasis of frames
+    pub fn emphasis(&self) -> Emphasis {
+        let emphasis = unsafe {
+            ffi::lame_get_emphasis(self.ptr())
+        };
+        Emphasis::from_raw(emphasis)
+    }
+
+    ///Returns results of gain analysis, if it is enabled.
+    ///
+    ///Analysis is complete only after flush, but it can be retrieved at any point to get intermediate results.
+    pub fn gain_analysis(&self) -> Option<GainAnalysis> {
+        let (find_replay_gain, decode_on_the_fly) = unsafe {
+            (ffi::lame_get_findReplayGain(self.ptr()) != 0, ffi::lame_get_decode_on_the_fly(self.ptr()) != 0)
+        };
+
+        if !find_replay_gain && !decode_on_the_fly {
+            return None;
+        }
+
+        let mut result = GainAnalysis {
+            radio_gain_db: None,
+            peak_sample: None,
+            noclip_gain_change_db: None,
+            noclip_scale: None,
+        };
+
+        unsafe {
+            //Gain values are multiplied by 10
+            if find_replay_gain {
+                result.radio_gain_db = Some(ffi::lame_get_RadioGain(self.ptr()) as f32 / 10.0);
+            }
+
+            if decode_on_the_fly {
+                result.peak_sample = Some(ffi::lame_get_PeakSample(self.ptr()) as f32);
+                result.noclip_gain_change_db = Some(ffi::lame_get_noclipGainChange(self.ptr()) as f32 / 10.0);
+                //Negative value indicates LAME cannot determine scale
+                let noclip_scale = ffi::lame_get_noclipScale(self.ptr()) as f32;
+                if noclip_scale > 0.0 {
+                    result.noclip_scale = Some(noclip_scale);
+                }
+            }
+        }
+
+        Some(result)
+    }
+
     #[inline]
     ///Returns indication whether encoder is configured to write LAME tag
     pub fn is_lame_tag_written(&self) -> bool {
@@ -841,11 +2250,19 @@ impl Encoder {
     ///### Result:
     ///On success, returns number of bytes written (can be 0).
     ///Otherwise returns error indicating potential issue.
-    pub fn encode(&mut self, input: impl EncoderInput, output: &mut [MaybeUninit<u8>]) -> Result<usize, EncodeError> {
+    ///
+    ///Input is validated against [Encoder::num_channels] before encoding (see [EncoderInput::validate]).
+    ///
+    ///Inputs, that are converted by this crate before passing to LAME (e.g. 8-bit or 24-bit PCM),
+    ///require `output` to fit worst case size (see [max_required_buffer_size]), including [Id3Tag] for first encoded data.
+    ///Otherwise [EncodeError::BufferTooSmall] is returned without encoding anything.
+    pub fn encode<I: EncoderInput>(&mut self, input: I, output: &mut [MaybeUninit<u8>]) -> Result<usize, EncodeError> where C: Accepts<I> {
+        input.validate(self.num_channels())?;
+
         let output_len = output.len();
         let output_buf = output.as_mut_ptr();
 
-        let result = input.encode(self, output_buf as _, output_len);
+        let result = input.encode(self.as_dynamic(), output_buf as _, output_len);
 
         EncodeError::from_c_int(result)
     }
@@ -856,7 +2273,7 @@ impl Encoder {
     ///`output` size is adjusted on success only
     ///
     ///Refer for details to `encode()`
-    pub fn encode_to_vec(&mut self, input: impl EncoderInput, output: &mut Vec<u8>) -> Result<usize, EncodeError> {
+    pub fn encode_to_vec<I: EncoderInput>(&mut self, input: I, output: &mut Vec<u8>) -> Result<usize, EncodeError> where C: Accepts<I> {
         let original_len = output.len();
         match self.encode(input, output.spare_capacity_mut()) {
             Ok(written) => {
@@ -889,7 +2306,7 @@ impl Encoder {
         let output_len = output.len();
         let output_buf = output.as_mut_ptr();
 
-        let result = T::flush(self, output_buf as _, output_len);
+        let result = T::flush(self.as_dynamic(), output_buf as _, output_len);
 
         EncodeError::from_c_int(result)
     }
@@ -912,21 +2329,78 @@ impl Encoder {
             Err(error) => Err(error),
         }
     }
+
+    //Adds space for Id3 tag, which is written with first encoded data
+    pub(crate) fn with_id3v2_tag_size(&self, size: usize) -> usize {
+        let frame_num = unsafe {
+            ffi::lame_get_frameNum(self.ptr())
+        };
+
+        match frame_num {
+            0 => size.saturating_add(self.id3v2_tag_size()),
+            _ => size,
+        }
+    }
+
+    ///Attempts to encode PCM data, reserving enough space within `output` buffer
+    ///
+    ///Space is reserved using [max_required_buffer_size] for number of samples in `input`.
+    ///Until first frame is encoded, space for [Id3Tag] is reserved too, as it is written with first encoded data.
+    ///
+    ///`output` size is adjusted on success only
+    ///
+    ///Refer for details to `encode()`
+    pub fn encode_into<I: EncoderInput>(&mut self, input: I, output: &mut impl EncoderOutput) -> Result<usize, EncodeError> where C: Accepts<I> {
+        let size = self.with_id3v2_tag_size(max_required_buffer_size(input.samples_num()));
+        let result = self.encode(input, output.reserve_spare(size));
+
+        match result {
+            Ok(written) => {
+                unsafe {
+                    output.advance(written);
+                }
+                Ok(written)
+            },
+            Err(error) => Err(error),
+        }
+    }
+
+    ///Attempts flush all data, reserving enough space within `output` buffer
+    ///
+    ///Space is reserved in the same way as in `encode_into()`
+    ///
+    ///`output` size is adjusted on success only
+    ///
+    ///Refer for details to `flush()`
+    pub fn flush_into<T: EncoderFlush>(&mut self, output: &mut impl EncoderOutput) -> Result<usize, EncodeError> {
+        let size = self.with_id3v2_tag_size(max_required_buffer_size(0).saturating_add(ID3V1_TAG_SIZE));
+        let result = self.flush::<T>(output.reserve_spare(size));
+
+        match result {
+            Ok(written) => {
+                unsafe {
+                    output.advance(written);
+                }
+                Ok(written)
+            },
+            Err(error) => Err(error),
+        }
+    }
 }
 
-impl Drop for Encoder {
+impl<C> Drop for Encoder<C> {
     #[inline]
     fn drop(&mut self) {
         unsafe {
-            ffi::lame_close(self.ptr())
+            ffi::lame_close(self.inner.as_ptr())
         };
     }
 }
 
 /// According to LAME 3.99.5 HACKING, it is thread-safe.
-unsafe impl Send for Encoder {}
+unsafe impl<C> Send for Encoder<C> {}
 /// According to LAME 3.99.5 HACKING, it is thread-safe.
-unsafe impl Sync for Encoder {}
+unsafe impl<C> Sync for Encoder<C> {}
 
 ///Creates default encoder with 192kbps bitrate and best possible quality.
 pub fn encoder() -> Result<Encoder, BuildError> {
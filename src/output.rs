@@ -0,0 +1,46 @@
+use alloc::vec::Vec;
+use core::mem::MaybeUninit;
+
+///Growable output buffer for encoder
+///
+///Used by [Encoder::encode_into](crate::Encoder::encode_into) and [Encoder::flush_into](crate::Encoder::flush_into)
+///to reserve necessary space automatically.
+///
+///Please note that while you can implement your own trait, it is your responsibility to ensure
+///that `advance` function is correct and safe.
+pub trait EncoderOutput {
+    ///Reserves space for at least `additional` bytes, returning uninitialized part of the buffer.
+    ///
+    ///Returned slice must be at least `additional` bytes long.
+    fn reserve_spare(&mut self, additional: usize) -> &mut [MaybeUninit<u8>];
+
+    ///Marks `len` bytes, previously written into slice returned by `reserve_spare`, as initialized.
+    unsafe fn advance(&mut self, len: usize);
+}
+
+impl EncoderOutput for Vec<u8> {
+    #[inline(always)]
+    fn reserve_spare(&mut self, additional: usize) -> &mut [MaybeUninit<u8>] {
+        self.reserve(additional);
+        self.spare_capacity_mut()
+    }
+
+    #[inline(always)]
+    unsafe fn advance(&mut self, len: usize) {
+        self.set_len(self.len().saturating_add(len));
+    }
+}
+
+#[cfg(feature = "bytes")]
+impl EncoderOutput for bytes::BytesMut {
+    #[inline(always)]
+    fn reserve_spare(&mut self, additional: usize) -> &mut [MaybeUninit<u8>] {
+        self.reserve(additional);
+        self.spare_capacity_mut()
+    }
+
+    #[inline(always)]
+    unsafe fn advance(&mut self, len: usize) {
+        self.set_len(self.len().saturating_add(len));
+    }
+}
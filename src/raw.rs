@@ -0,0 +1,186 @@
+use super::{Encoder, EncoderInput, EncodeError};
+use super::input::encode_converted;
+
+use core::ffi::c_int;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Format of single PCM sample
+pub enum SampleFormat {
+    ///8-bit unsigned integer
+    U8,
+    ///8-bit signed integer
+    S8,
+    ///16-bit unsigned integer
+    U16,
+    ///16-bit signed integer
+    S16,
+    ///24-bit signed integer, packed into 3 bytes
+    S24,
+    ///32-bit signed integer
+    S32,
+    ///32-bit float within range `-1.0..=1.0`
+    F32,
+    ///64-bit float within range `-1.0..=1.0`
+    F64,
+}
+
+impl SampleFormat {
+    #[inline]
+    ///Returns size of single sample in bytes
+    pub const fn sample_size(self) -> usize {
+        match self {
+            Self::U8 | Self::S8 => 1,
+            Self::U16 | Self::S16 => 2,
+            Self::S24 => 3,
+            Self::S32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+
+    //Reads sample from start of `bytes`, converting it into float
+    #[inline]
+    fn read(self, endian: Endian, bytes: &[u8]) -> f32 {
+        macro_rules! from_bytes {
+            ($ty:ty, $len:literal) => {{
+                let mut sample = [0u8; $len];
+                sample.copy_from_slice(&bytes[..$len]);
+                match endian {
+                    Endian::Little => <$ty>::from_le_bytes(sample),
+                    Endian::Big => <$ty>::from_be_bytes(sample),
+                }
+            }};
+        }
+
+        match self {
+            Self::U8 => (bytes[0] as f32 - 128.0) / 128.0,
+            Self::S8 => bytes[0] as i8 as f32 / 128.0,
+            Self::U16 => (from_bytes!(u16, 2) as f32 - 32_768.0) / 32_768.0,
+            Self::S16 => from_bytes!(i16, 2) as f32 / 32_768.0,
+            Self::S24 => {
+                let sample = match endian {
+                    Endian::Little => i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]),
+                    Endian::Big => i32::from_be_bytes([bytes[0], bytes[1], bytes[2], 0]),
+                };
+                //Lowest byte is always 0, so it is the same as scaling 24-bit sample
+                (sample as f64 / 2_147_483_648.0) as f32
+            },
+            Self::S32 => (from_bytes!(i32, 4) as f64 / 2_147_483_648.0) as f32,
+            Self::F32 => from_bytes!(f32, 4),
+            Self::F64 => from_bytes!(f64, 8) as f32,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Byte order of samples
+pub enum Endian {
+    ///Little endian
+    Little,
+    ///Big endian
+    Big,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Layout of channels
+pub enum Layout {
+    ///Samples of channels alternate: `[<left>, <right>...]`
+    Interleaved,
+    ///Samples of each channel are stored continuously one after another: `[<left>..., <right>...]`
+    ///
+    ///Each channel takes equal part of bytes.
+    Planar,
+}
+
+#[derive(Debug, Copy, Clone)]
+///PCM data with format, described at runtime.
+///
+///Bytes are not required to be aligned, as samples are read byte by byte and converted in small chunks,
+///without allocating intermediate buffer.
+///
+///Number of channels must match encoder's [num_channels](Encoder::num_channels), otherwise [EncodeError::ChannelMismatch] is reported.
+///Bytes must contain only complete samples for every channel, otherwise [EncodeError::OddInterleavedLength] or [EncodeError::UnequalChannelLengths]
+///is reported, depending on layout.
+pub struct RawPcm<'a> {
+    ///PCM data
+    pub bytes: &'a [u8],
+    ///Format of samples
+    pub format: SampleFormat,
+    ///Byte order of samples
+    pub endian: Endian,
+    ///Layout of channels
+    pub layout: Layout,
+    ///Number of channels (either 1 or 2)
+    pub channels: u8,
+}
+
+impl EncoderInput for RawPcm<'_> {
+    #[inline]
+    fn samples_num(&self) -> usize {
+        match self.channels {
+            0 => 0,
+            channels => self.bytes.len() / (self.format.sample_size() * channels as usize),
+        }
+    }
+
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        if self.channels == 0 || self.channels > 2 {
+            return Err(EncodeError::UnsupportedChannels);
+        } else if self.channels != num_channels {
+            return Err(EncodeError::ChannelMismatch);
+        }
+
+        if self.bytes.len() % (self.format.sample_size() * self.channels as usize) == 0 {
+            Ok(())
+        } else {
+            match self.layout {
+                Layout::Interleaved => Err(EncodeError::OddInterleavedLength),
+                Layout::Planar => Err(EncodeError::UnequalChannelLengths),
+            }
+        }
+    }
+
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        //Normally checked by validate(), but it is necessary to ensure `channels` is valid for LAME
+        if self.channels == 0 || self.channels > 2 {
+            return EncodeError::UNSUPPORTED_CHANNELS;
+        } else if self.channels != encoder.num_channels() {
+            return EncodeError::CHANNEL_MISMATCH;
+        }
+
+        let samples_num = self.samples_num();
+        let sample_size = self.format.sample_size();
+        let is_stereo = self.channels == 2;
+        let (format, endian) = (self.format, self.endian);
+
+        match self.layout {
+            Layout::Interleaved => {
+                let frame_size = sample_size * self.channels as usize;
+                encode_converted(encoder, samples_num, is_stereo, output_buf, output_len, |offset, left, right| {
+                    let frames = self.bytes[offset * frame_size..].chunks_exact(frame_size);
+                    for ((idx, left), frame) in left.iter_mut().enumerate().zip(frames) {
+                        *left = format.read(endian, frame);
+                        if is_stereo {
+                            right[idx] = format.read(endian, &frame[sample_size..]);
+                        }
+                    }
+                })
+            },
+            Layout::Planar => {
+                let plane_len = self.bytes.len() / self.channels as usize;
+                let (left_plane, right_plane) = self.bytes.split_at(plane_len);
+                encode_converted(encoder, samples_num, is_stereo, output_buf, output_len, |offset, left, right| {
+                    let samples = left_plane[offset * sample_size..].chunks_exact(sample_size);
+                    for (left, sample) in left.iter_mut().zip(samples) {
+                        *left = format.read(endian, sample);
+                    }
+                    if is_stereo {
+                        let samples = right_plane[offset * sample_size..].chunks_exact(sample_size);
+                        for (right, sample) in right.iter_mut().zip(samples) {
+                            *right = format.read(endian, sample);
+                        }
+                    }
+                })
+            },
+        }
+    }
+}
@@ -0,0 +1,294 @@
+use super::{Bitrate, Builder, BuildError, Channels, Dynamic, Emphasis, Encoder, Filter, Gain, Id3TagBuilder, Id3Version, Mode, Preset, Quality, VbrMode};
+
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::num::NonZeroU32;
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+///Owned ID3 tag, written using [Id3TagBuilder]
+pub struct Id3TagSettings {
+    ///Title
+    pub title: Option<String>,
+    ///Artist
+    pub artist: Option<String>,
+    ///Album
+    pub album: Option<String>,
+    ///Album art image (PNG, JPG or GIF)
+    pub album_art: Vec<u8>,
+    ///Year
+    pub year: Option<String>,
+    ///Comment
+    pub comment: Option<String>,
+    ///Genre, either ID3v1 genre name/number or arbitrary text
+    pub genre: Option<String>,
+    ///Track number with optional total number of tracks
+    pub track: Option<(u16, Option<u16>)>,
+    ///Disc number with optional total number of discs
+    pub disc: Option<(u16, Option<u16>)>,
+    ///User defined text as pairs of description and value
+    pub user_text: Vec<(String, String)>,
+    ///Arbitrary ID3v2 text frames as pairs of frame id and value
+    pub frames: Vec<(String, String)>,
+    ///Versions of tag to write
+    pub version: Id3Version,
+    ///Number of padding bytes to add after ID3v2 tag
+    pub padding: Option<usize>,
+}
+
+impl Id3TagSettings {
+    ///Creates tag builder, borrowing values from `self`
+    pub fn tag_builder(&self) -> Id3TagBuilder<'_> {
+        let mut builder = Id3TagBuilder::new().with_album_art(&self.album_art).with_version(self.version);
+
+        if let Some(title) = self.title.as_deref() {
+            builder = builder.with_title(title);
+        }
+        if let Some(artist) = self.artist.as_deref() {
+            builder = builder.with_artist(artist);
+        }
+        if let Some(album) = self.album.as_deref() {
+            builder = builder.with_album(album);
+        }
+        if let Some(year) = self.year.as_deref() {
+            builder = builder.with_year(year);
+        }
+        if let Some(comment) = self.comment.as_deref() {
+            builder = builder.with_comment(comment);
+        }
+        if let Some(genre) = self.genre.as_deref() {
+            builder = builder.with_genre(genre);
+        }
+        if let Some((number, total)) = self.track {
+            builder = builder.with_track(number, total);
+        }
+        if let Some((number, total)) = self.disc {
+            builder = builder.with_disc(number, total);
+        }
+        for (description, value) in self.user_text.iter() {
+            builder = builder.with_user_text(description, value);
+        }
+        for (id, value) in self.frames.iter() {
+            builder = builder.with_frame(id, value);
+        }
+        if let Some(padding) = self.padding {
+            builder = builder.with_padding(padding);
+        }
+
+        builder
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+///Encoder parameters as plain data, that can be used to create any number of encoders.
+///
+///Each parameter corresponds to [Builder] method of the same name.
+///Parameters, that are `None`, are left as LAME's default.
+///
+///## Example
+///
+///```rust
+///use mp3lame_encoder::{Bitrate, EncoderSettings, Quality};
+///
+///let settings = EncoderSettings {
+///    num_channels: Some(2),
+///    sample_rate: Some(44_100),
+///    brate: Some(Bitrate::Kbps192),
+///    quality: Some(Quality::Best),
+///    ..EncoderSettings::default()
+///};
+///
+///let mp3_encoders = (0..4).map(|_| settings.build()).collect::<Result<Vec<_>, _>>().expect("To initialize LAME encoders");
+///```
+pub struct EncoderSettings {
+    ///Preset, applied before any other parameter
+    pub preset: Option<Preset>,
+    ///Input sample rate
+    pub sample_rate: Option<u32>,
+    ///Number of input channels
+    pub num_channels: Option<u8>,
+    ///Output sample rate
+    pub output_sample_rate: Option<NonZeroU32>,
+    ///VBR mode
+    pub vbr_mode: Option<VbrMode>,
+    ///Constant bitrate
+    pub brate: Option<Bitrate>,
+    ///VBR quality
+    pub vbr_quality: Option<Quality>,
+    ///ABR target bitrate
+    pub vbr_mean_bitrate: Option<Bitrate>,
+    ///Minimum VBR bitrate
+    pub vbr_min_bitrate: Option<Bitrate>,
+    ///Maximum VBR bitrate
+    pub vbr_max_bitrate: Option<Bitrate>,
+    ///Whether minimum VBR bitrate is strictly enforced
+    pub vbr_hard_min: Option<bool>,
+    ///MPEG mode
+    pub mode: Option<Mode>,
+    ///Algorithm quality
+    pub quality: Option<Quality>,
+    ///Lowpass filter
+    pub lowpass: Option<Filter>,
+    ///Width of lowpass filter transition band (Hz)
+    pub lowpass_width: Option<NonZeroU32>,
+    ///Highpass filter
+    pub highpass: Option<Filter>,
+    ///Width of highpass filter transition band (Hz)
+    pub highpass_width: Option<NonZeroU32>,
+    ///Gain, applied to all input samples
+    pub scale: Option<Gain>,
+    ///Gain of left and right input channels
+    pub channel_scale: Option<(Gain, Gain)>,
+    ///Copyright bit of frame header
+    pub copyright: Option<bool>,
+    ///Original bit of frame header
+    pub original: Option<bool>,
+    ///Private extension bit of frame header
+    pub extension: Option<bool>,
+    ///Whether frames are protected by CRC
+    pub crc: Option<bool>,
+    ///Emphasis of frame header
+    pub emphasis: Option<Emphasis>,
+    ///Whether to use free format bitrate
+    pub free_format: Option<bool>,
+    ///Whether to enforce strict ISO compliance
+    pub strict_iso: Option<bool>,
+    ///Whether to disable bit reservoir
+    pub disable_reservoir: Option<bool>,
+    ///Whether to write VBR tag
+    pub to_write_vbr_tag: Option<bool>,
+    ///Whether to perform ReplayGain analysis
+    pub find_replay_gain: Option<bool>,
+    ///Whether to decode on the fly to find peak sample
+    pub decode_on_the_fly: Option<bool>,
+    ///ID3 tag
+    pub id3_tag: Option<Id3TagSettings>,
+}
+
+impl EncoderSettings {
+    ///Configures `builder` with parameters that are set.
+    ///
+    ///Preset is applied first, followed by sample rates and number of channels, so that
+    ///parameters validated against them are set after.
+    pub fn configure(&self, builder: &mut Builder) -> Result<(), BuildError> {
+        if let Some(preset) = self.preset {
+            builder.set_preset(preset)?;
+        }
+        if let Some(rate) = self.sample_rate {
+            builder.set_sample_rate(rate)?;
+        }
+        if let Some(num) = self.num_channels {
+            builder.set_num_channels(num)?;
+        }
+        if let Some(rate) = self.output_sample_rate {
+            builder.set_output_sample_rate(Some(rate))?;
+        }
+        if let Some(mode) = self.vbr_mode {
+            builder.set_vbr_mode(mode)?;
+        }
+        if let Some(brate) = self.brate {
+            builder.set_brate(brate)?;
+        }
+        if let Some(quality) = self.vbr_quality {
+            builder.set_vbr_quality(quality)?;
+        }
+        if let Some(brate) = self.vbr_mean_bitrate {
+            builder.set_vbr_mean_bitrate(brate)?;
+        }
+        if let Some(brate) = self.vbr_min_bitrate {
+            builder.set_vbr_min_bitrate(brate)?;
+        }
+        if let Some(brate) = self.vbr_max_bitrate {
+            builder.set_vbr_max_bitrate(brate)?;
+        }
+        if let Some(value) = self.vbr_hard_min {
+            builder.set_vbr_hard_min(value)?;
+        }
+        if let Some(mode) = self.mode {
+            builder.set_mode(mode)?;
+        }
+        if let Some(quality) = self.quality {
+            builder.set_quality(quality)?;
+        }
+        if let Some(filter) = self.lowpass {
+            builder.set_lowpass(Some(filter))?;
+        }
+        if let Some(width) = self.lowpass_width {
+            builder.set_lowpass_width(Some(width))?;
+        }
+        if let Some(filter) = self.highpass {
+            builder.set_highpass(Some(filter))?;
+        }
+        if let Some(width) = self.highpass_width {
+            builder.set_highpass_width(Some(width))?;
+        }
+        if let Some(gain) = self.scale {
+            builder.set_scale(gain)?;
+        }
+        if let Some((left, right)) = self.channel_scale {
+            builder.set_channel_scale(left, right)?;
+        }
+        if let Some(value) = self.copyright {
+            builder.set_copyright(value)?;
+        }
+        if let Some(value) = self.original {
+            builder.set_original(value)?;
+        }
+        if let Some(value) = self.extension {
+            builder.set_extension(value)?;
+        }
+        if let Some(value) = self.crc {
+            builder.set_crc(value)?;
+        }
+        if let Some(emphasis) = self.emphasis {
+            builder.set_emphasis(emphasis)?;
+        }
+        if let Some(value) = self.free_format {
+            builder.set_free_format(value)?;
+        }
+        if let Some(value) = self.strict_iso {
+            builder.set_strict_iso(value)?;
+        }
+        if let Some(value) = self.disable_reservoir {
+            builder.set_disable_reservoir(value)?;
+        }
+        if let Some(value) = self.to_write_vbr_tag {
+            builder.set_to_write_vbr_tag(value)?;
+        }
+        if let Some(value) = self.find_replay_gain {
+            builder.set_find_replay_gain(value)?;
+        }
+        if let Some(value) = self.decode_on_the_fly {
+            builder.set_decode_on_the_fly(value)?;
+        }
+        if let Some(tag) = self.id3_tag.as_ref() {
+            builder.set_id3_tag_builder(&tag.tag_builder()).map_err(BuildError::Id3Tag)?;
+        }
+
+        Ok(())
+    }
+
+    ///Creates new builder, configured with parameters that are set.
+    pub fn builder(&self) -> Result<Builder, BuildError> {
+        let mut builder = Builder::new().ok_or(BuildError::NoMem)?;
+        self.configure(&mut builder)?;
+        Ok(builder)
+    }
+
+    #[inline]
+    ///Creates new encoder with parameters that are set.
+    pub fn build(&self) -> Result<Encoder, BuildError> {
+        self.build_typed::<Dynamic>()
+    }
+
+    #[inline]
+    ///Creates new encoder with parameters that are set and number of channels known at compile time.
+    ///
+    ///Refer for details to [Builder::build_typed]
+    pub fn build_typed<C: Channels>(&self) -> Result<Encoder<C>, BuildError> {
+        self.builder()?.build_typed()
+    }
+}
@@ -0,0 +1,133 @@
+use super::{ffi, Bitrate, Channels, Encoder, Mode};
+
+use alloc::vec::Vec;
+use core::ffi::c_int;
+use core::time::Duration;
+
+//Number of bitrates in LAME's histograms, excluding free format
+const BITRATES_NUM: usize = 14;
+
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+///Number of granules per block type
+pub struct BlockTypeStats {
+    ///Long blocks
+    pub long: u32,
+    ///Start blocks, transition from long to short
+    pub start: u32,
+    ///Short blocks
+    pub short: u32,
+    ///Stop blocks, transition from short to long
+    pub stop: u32,
+    ///Mixed blocks
+    pub mixed: u32,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+///Statistics of encoded frames
+///
+///Retrieved via [Encoder::stats]
+pub struct EncodeStats {
+    ///Number of frames encoded so far.
+    ///
+    ///Frame is counted once LAME encodes it, even if its data is still buffered until next encode or flush.
+    ///Samples, that LAME buffers for lookahead, are encoded only on flush with [FlushGap](crate::FlushGap).
+    ///Flush with [FlushNoGap](crate::FlushNoGap) keeps them for next stream and so they're not counted.
+    pub frames: u32,
+    ///Estimated total number of frames, if number of samples is known to LAME
+    pub total_frames: Option<u32>,
+    ///Number of frames per bitrate, including every bitrate of output MPEG version.
+    ///
+    ///Free format frames are not included, as their bitrate is not one of [Bitrate].
+    pub bitrates: Vec<(Bitrate, u32)>,
+    ///Number of frames per mode.
+    ///
+    ///Stereo frames are reported as either [Mode::Stereo] or [Mode::JointStereo] (mid/side stereo).
+    pub modes: Vec<(Mode, u32)>,
+    ///Number of granules per block type
+    pub block_types: BlockTypeStats,
+    ///Average bitrate (as kbps) of encoded frames
+    pub average_bitrate: f32,
+    ///Duration of encoded frames, including encoder delay and padding.
+    ///
+    ///Calculated from [frames](Self::frames), hence it doesn't include samples, that are not yet encoded.
+    pub duration: Duration,
+}
+
+impl<C: Channels> Encoder<C> {
+    ///Returns statistics of frames encoded so far.
+    ///
+    ///Can be called at any point, but statistics are complete only after flush.
+    pub fn stats(&self) -> EncodeStats {
+        let ptr = self.ptr();
+        let mut bitrate_kbps = [0 as c_int; BITRATES_NUM];
+        let mut bitrate_hist = [0 as c_int; BITRATES_NUM];
+        //LR, LR with intensity stereo, MS, MS with intensity stereo
+        let mut stereo_mode_hist = [0 as c_int; 4];
+        //long, start, short, stop, mixed and sum of all
+        let mut block_type_hist = [0 as c_int; 6];
+        let (frames, total_frames, frame_size, output_sample_rate, mode) = unsafe {
+            ffi::lame_bitrate_kbps(ptr, bitrate_kbps.as_mut_ptr());
+            ffi::lame_bitrate_hist(ptr, bitrate_hist.as_mut_ptr());
+            ffi::lame_stereo_mode_hist(ptr, stereo_mode_hist.as_mut_ptr());
+            ffi::lame_block_type_hist(ptr, block_type_hist.as_mut_ptr());
+            (
+                ffi::lame_get_frameNum(ptr),
+                ffi::lame_get_totalframes(ptr),
+                ffi::lame_get_framesize(ptr),
+                ffi::lame_get_out_samplerate(ptr),
+                Mode::from_raw(ffi::lame_get_mode(ptr) as _),
+            )
+        };
+        let count = |count: c_int| u32::try_from(count).unwrap_or(0);
+
+        let mut bitrates = Vec::with_capacity(BITRATES_NUM);
+        let mut total_kbps = 0u64;
+        let mut bitrate_frames = 0u64;
+        for (kbps, frames) in bitrate_kbps.iter().zip(bitrate_hist.iter()) {
+            let frames = count(*frames);
+            if let Some(bitrate) = u16::try_from(*kbps).ok().and_then(Bitrate::from_kbps) {
+                bitrates.push((bitrate, frames));
+            }
+            if *kbps > 0 {
+                total_kbps += *kbps as u64 * frames as u64;
+                bitrate_frames += frames as u64;
+            }
+        }
+
+        let modes = match mode {
+            Mode::Mono => alloc::vec![(Mode::Mono, count(stereo_mode_hist[0]))],
+            _ => alloc::vec![
+                (Mode::Stereo, count(stereo_mode_hist[0]) + count(stereo_mode_hist[1])),
+                (Mode::JointStereo, count(stereo_mode_hist[2]) + count(stereo_mode_hist[3])),
+            ],
+        };
+
+        let frames = count(frames);
+        let samples = frames as u64 * count(frame_size) as u64;
+        let duration = match count(output_sample_rate) as u64 {
+            0 => Duration::ZERO,
+            rate => Duration::from_secs(samples / rate) + Duration::from_nanos((samples % rate) * 1_000_000_000 / rate),
+        };
+
+        EncodeStats {
+            frames,
+            total_frames: Some(count(total_frames)).filter(|frames| *frames > 0),
+            bitrates,
+            modes,
+            block_types: BlockTypeStats {
+                long: count(block_type_hist[0]),
+                start: count(block_type_hist[1]),
+                short: count(block_type_hist[2]),
+                stop: count(block_type_hist[3]),
+                mixed: count(block_type_hist[4]),
+            },
+            average_bitrate: match bitrate_frames {
+                0 => 0.0,
+                frames => (total_kbps as f64 / frames as f64) as f32,
+            },
+            duration,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use super::{ffi, Encoder, EncoderInput, EncodeError};
+use super::input::encode_converted;
+
+use core::ptr;
+use core::ffi::c_int;
+
+use symphonia_core::audio::{AudioBuffer, AudioBufferRef, Signal, SignalSpec};
+use symphonia_core::conv::FromSample;
+use symphonia_core::sample::{i24, u24, Sample};
+
+//Sample type of symphonia's planar buffer
+trait PlanarSample: Sample {
+    //Encodes single channel (if `right` is `None`) or two channels
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int;
+}
+
+impl PlanarSample for i16 {
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer(encoder.ptr(), left.as_ptr(), right.map_or(ptr::null(), <[Self]>::as_ptr), left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+impl PlanarSample for i32 {
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer_int(encoder.ptr(), left.as_ptr() as _, right.map_or(ptr::null(), <[Self]>::as_ptr) as _, left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+impl PlanarSample for f32 {
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer_ieee_float(encoder.ptr(), left.as_ptr(), right.map_or(ptr::null(), <[Self]>::as_ptr), left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+impl PlanarSample for f64 {
+    #[inline(always)]
+    fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+        unsafe {
+            ffi::lame_encode_buffer_ieee_double(encoder.ptr(), left.as_ptr(), right.map_or(ptr::null(), <[Self]>::as_ptr), left.len() as _, output_buf as _, output_len as _)
+        }
+    }
+}
+
+#[inline]
+fn validate_spec(spec: &SignalSpec, num_channels: u8) -> Result<(), EncodeError> {
+    match spec.channels.count() {
+        count if count == 0 || count > 2 => Err(EncodeError::UnsupportedChannels),
+        count if count != num_channels as usize => Err(EncodeError::ChannelMismatch),
+        _ => Ok(()),
+    }
+}
+
+//LAME has no direct support for these, so they're converted to full scale i32 chunk by chunk
+macro_rules! impl_converted_planar_sample {
+    ($($ty:ty),+) => {$(
+        impl PlanarSample for $ty {
+            #[inline]
+            fn encode_planes(encoder: &mut Encoder, left: &[Self], right: Option<&[Self]>, output_buf: *mut u8, output_len: usize) -> c_int {
+                encode_converted::<c_int>(encoder, left.len(), right.is_some(), output_buf, output_len, |offset, left_chunk, right_chunk| {
+                    let len = left_chunk.len();
+                    for (converted, sample) in left_chunk.iter_mut().zip(&left[offset..offset + len]) {
+                        *converted = i32::from_sample(*sample);
+                    }
+                    if let Some(right) = right {
+                        for (converted, sample) in right_chunk.iter_mut().zip(&right[offset..offset + len]) {
+                            *converted = i32::from_sample(*sample);
+                        }
+                    }
+                })
+            }
+        }
+    )+};
+}
+
+impl_converted_planar_sample!(u8, u16, u24, u32, i8, i24);
+
+///Available with `symphonia` feature.
+///
+///Only mono and stereo buffers are supported, otherwise [EncodeError::UnsupportedChannels] is reported.
+///Number of channels must match encoder, otherwise [EncodeError::ChannelMismatch] is reported.
+impl<S: PlanarSample> EncoderInput for &AudioBuffer<S> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.frames()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_spec(self.spec(), num_channels)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        match self.spec().channels.count() {
+            1 => S::encode_planes(encoder, self.chan(0), None, output_buf, output_len),
+            2 => S::encode_planes(encoder, self.chan(0), Some(self.chan(1)), output_buf, output_len),
+            _ => EncodeError::UNSUPPORTED_CHANNELS,
+        }
+    }
+}
+
+///Available with `symphonia` feature.
+///
+///Only mono and stereo buffers are supported, otherwise [EncodeError::UnsupportedChannels] is reported.
+///Number of channels must match encoder, otherwise [EncodeError::ChannelMismatch] is reported.
+impl EncoderInput for &AudioBufferRef<'_> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.frames()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_spec(self.spec(), num_channels)
+    }
+
+    #[inline]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        match self {
+            AudioBufferRef::U8(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::U16(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::U24(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::U32(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::S8(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::S16(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::S24(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::S32(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::F32(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+            AudioBufferRef::F64(buf) => buf.as_ref().encode(encoder, output_buf, output_len),
+        }
+    }
+}
+
+///Available with `symphonia` feature.
+///
+///Refer to implementation for `&AudioBufferRef`
+impl EncoderInput for AudioBufferRef<'_> {
+    #[inline(always)]
+    fn samples_num(&self) -> usize {
+        self.frames()
+    }
+
+    #[inline(always)]
+    fn validate(&self, num_channels: u8) -> Result<(), EncodeError> {
+        validate_spec(self.spec(), num_channels)
+    }
+
+    #[inline(always)]
+    fn encode(self, encoder: &mut Encoder, output_buf: *mut u8, output_len: usize) -> c_int {
+        (&self).encode(encoder, output_buf, output_len)
+    }
+}
@@ -0,0 +1,257 @@
+use super::{Builder, BuildError, Endian, FlushNoGap, Layout, Mp3Writer, RawPcm, SampleFormat};
+
+use std::io::{self, Read, Seek, Write};
+
+const WAVE_FORMAT_PCM: u16 = 1;
+const WAVE_FORMAT_IEEE_FLOAT: u16 = 3;
+const WAVE_FORMAT_EXTENSIBLE: u16 = 0xFFFE;
+//Number of frames read from data chunk at once
+const FRAMES_PER_CHUNK: usize = 4096;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///Format of WAV samples
+pub enum WavSampleFormat {
+    ///8-bit unsigned PCM
+    U8,
+    ///16-bit signed PCM
+    I16,
+    ///24-bit signed PCM
+    I24,
+    ///32-bit signed PCM
+    I32,
+    ///32-bit IEEE float
+    F32,
+    ///64-bit IEEE float
+    F64,
+}
+
+impl WavSampleFormat {
+    #[inline]
+    ///Returns size of single sample in bytes
+    pub const fn sample_size(self) -> usize {
+        self.raw_format().sample_size()
+    }
+
+    #[inline]
+    const fn raw_format(self) -> SampleFormat {
+        match self {
+            Self::U8 => SampleFormat::U8,
+            Self::I16 => SampleFormat::S16,
+            Self::I24 => SampleFormat::S24,
+            Self::I32 => SampleFormat::S32,
+            Self::F32 => SampleFormat::F32,
+            Self::F64 => SampleFormat::F64,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+///RIFF/WAVE header
+pub struct WavHeader {
+    ///Sample rate
+    pub sample_rate: u32,
+    ///Number of channels (either 1 or 2)
+    pub num_channels: u8,
+    ///Format of samples
+    pub sample_format: WavSampleFormat,
+    ///Size of data chunk in bytes.
+    ///
+    ///`None` if it is unknown (i.e. WAV is streamed with size set to `u32::MAX`), in which case data continues until end of input.
+    pub data_len: Option<u32>,
+}
+
+#[inline(always)]
+fn invalid_data(error: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+#[inline]
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut result = [0u8; N];
+    reader.read_exact(&mut result)?;
+    Ok(result)
+}
+
+#[inline]
+fn skip<R: Read>(reader: &mut R, len: u64) -> io::Result<()> {
+    let skipped = io::copy(&mut reader.take(len), &mut io::sink())?;
+    if skipped == len {
+        Ok(())
+    } else {
+        Err(io::ErrorKind::UnexpectedEof.into())
+    }
+}
+
+impl WavHeader {
+    ///Reads header from `reader`, leaving it positioned at the start of samples.
+    ///
+    ///Supports PCM (8, 16, 24 and 32 bits) and IEEE float (32 and 64 bits) formats,
+    ///including `WAVE_FORMAT_EXTENSIBLE`.
+    ///
+    ///Returns error of kind `InvalidData` if input is not supported.
+    pub fn read<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let riff = read_array::<_, 12>(reader)?;
+        if &riff[..4] != b"RIFF" || &riff[8..] != b"WAVE" {
+            return Err(invalid_data("not a RIFF/WAVE file"));
+        }
+
+        let mut format = None;
+        loop {
+            let chunk = read_array::<_, 8>(reader)?;
+            let chunk_len = u32::from_le_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]);
+            //Chunks are aligned to 2 bytes
+            let padded_len = chunk_len as u64 + (chunk_len & 1) as u64;
+
+            match &chunk[..4] {
+                b"fmt " => {
+                    if chunk_len < 16 {
+                        return Err(invalid_data("fmt chunk is too short"));
+                    }
+                    let mut fmt = alloc::vec![0u8; chunk_len as usize];
+                    reader.read_exact(&mut fmt)?;
+                    skip(reader, padded_len - chunk_len as u64)?;
+                    format = Some(Self::parse_format(&fmt)?);
+                },
+                b"data" => match format {
+                    Some((sample_rate, num_channels, sample_format)) => return Ok(Self {
+                        sample_rate,
+                        num_channels,
+                        sample_format,
+                        data_len: match chunk_len {
+                            u32::MAX => None,
+                            len => Some(len),
+                        },
+                    }),
+                    None => return Err(invalid_data("data chunk precedes fmt chunk")),
+                },
+                _ => skip(reader, padded_len)?,
+            }
+        }
+    }
+
+    fn parse_format(fmt: &[u8]) -> io::Result<(u32, u8, WavSampleFormat)> {
+        let mut tag = u16::from_le_bytes([fmt[0], fmt[1]]);
+        let num_channels = u16::from_le_bytes([fmt[2], fmt[3]]);
+        let sample_rate = u32::from_le_bytes([fmt[4], fmt[5], fmt[6], fmt[7]]);
+        let bits_per_sample = u16::from_le_bytes([fmt[14], fmt[15]]);
+
+        if tag == WAVE_FORMAT_EXTENSIBLE {
+            //Sub format GUID starts with actual format tag
+            if fmt.len() < 40 {
+                return Err(invalid_data("WAVE_FORMAT_EXTENSIBLE fmt chunk is too short"));
+            }
+            tag = u16::from_le_bytes([fmt[24], fmt[25]]);
+        }
+
+        let num_channels = match num_channels {
+            1 | 2 => num_channels as u8,
+            _ => return Err(invalid_data("only mono and stereo WAV can be encoded")),
+        };
+
+        let sample_format = match (tag, bits_per_sample) {
+            (WAVE_FORMAT_PCM, 8) => WavSampleFormat::U8,
+            (WAVE_FORMAT_PCM, 16) => WavSampleFormat::I16,
+            (WAVE_FORMAT_PCM, 24) => WavSampleFormat::I24,
+            (WAVE_FORMAT_PCM, 32) => WavSampleFormat::I32,
+            (WAVE_FORMAT_IEEE_FLOAT, 32) => WavSampleFormat::F32,
+            (WAVE_FORMAT_IEEE_FLOAT, 64) => WavSampleFormat::F64,
+            _ => return Err(invalid_data("unsupported WAV sample format")),
+        };
+
+        Ok((sample_rate, num_channels, sample_format))
+    }
+
+    #[inline(always)]
+    ///Returns size of single frame (sample of every channel) in bytes
+    pub const fn frame_size(&self) -> usize {
+        self.sample_format.sample_size() * self.num_channels as usize
+    }
+
+    #[inline]
+    ///Sets input sample rate and number of channels of `builder`
+    pub fn configure(&self, builder: &mut Builder) -> Result<(), BuildError> {
+        builder.set_sample_rate(self.sample_rate)?;
+        builder.set_num_channels(self.num_channels)
+    }
+}
+
+//Reads as much as possible into `buffer`, returning number of bytes read
+fn read_full<R: Read>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut len = 0;
+    while len < buffer.len() {
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(len)
+}
+
+///Encodes samples of WAV file into `writer`, returning number of frames (samples per channel) encoded.
+///
+///`reader` must be positioned at the start of samples (see [WavHeader::read]).
+///Incomplete frame at the end of data is ignored.
+///
+///Encoder of `writer` must be configured using `header` (see [WavHeader::configure]).
+pub fn encode_wav_data<R: Read, W: Write + Seek>(header: &WavHeader, reader: R, writer: &mut Mp3Writer<W>) -> io::Result<u64> {
+    let mut reader = reader.take(header.data_len.map_or(u64::MAX, u64::from));
+    let frame_size = header.frame_size();
+    let mut buffer = alloc::vec![0u8; frame_size * FRAMES_PER_CHUNK];
+    let mut frames = 0u64;
+
+    loop {
+        let len = read_full(&mut reader, &mut buffer)?;
+        let len = len - len % frame_size;
+        if len == 0 {
+            break;
+        }
+
+        writer.encode(RawPcm {
+            bytes: &buffer[..len],
+            format: header.sample_format.raw_format(),
+            endian: Endian::Little,
+            layout: Layout::Interleaved,
+            channels: header.num_channels,
+        })?;
+        frames = frames.saturating_add((len / frame_size) as u64);
+    }
+
+    Ok(frames)
+}
+
+///Transcodes WAV file from `reader` into MP3 written to `writer`.
+///
+///Sample rate and number of channels of `builder` are set from WAV header, while other parameters are left as they are.
+///
+///Once encoding is done, LAME tag (if enabled) is written after [Id3Tag](crate::Id3Tag), hence `writer` must be seekable.
+///
+///Returns `writer` positioned at the end of MP3 data.
+///
+///## Example
+///
+///```rust,no_run
+///use mp3lame_encoder::{Builder, wav_to_mp3};
+///
+///let builder = Builder::new().expect("Create LAME builder")
+///    .with_brate(mp3lame_encoder::Bitrate::Kbps192).expect("set brate");
+///
+///let input = std::io::BufReader::new(std::fs::File::open("input.wav").expect("open input"));
+///let output = std::fs::File::create("output.mp3").expect("create output");
+///wav_to_mp3(input, builder, output).expect("To transcode");
+///```
+pub fn wav_to_mp3<R: Read, W: Write + Seek>(mut reader: R, mut builder: Builder, writer: W) -> io::Result<W> {
+    let header = WavHeader::read(&mut reader)?;
+    if let Err(error) = header.configure(&mut builder) {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, error));
+    }
+    let encoder = match builder.build() {
+        Ok(encoder) => encoder,
+        Err(error) => return Err(io::Error::new(io::ErrorKind::InvalidInput, error)),
+    };
+
+    let mut writer = Mp3Writer::new(encoder, writer)?;
+    encode_wav_data(&header, reader, &mut writer)?;
+    writer.finish::<FlushNoGap>()
+}
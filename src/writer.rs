@@ -0,0 +1,132 @@
+use super::{Accepts, Channels, Dynamic, Encoder, EncoderInput, EncoderFlush};
+
+use alloc::vec::Vec;
+use std::io::{self, Write, Seek, SeekFrom};
+
+///MP3 writer, streaming encoded data into `W`
+///
+///Takes care of:
+///
+///- Reserving enough space for encoder output (see [Encoder::encode_into]);
+///- Writing [Id3Tag](crate::Id3Tag) which is emitted with first encoded data;
+///- Writing LAME tag after [Id3Tag](crate::Id3Tag) on [finish](Mp3Writer::finish), if encoder is configured to write it.
+///
+///LAME reserves space for its tag at the beginning of the stream, hence `W` must be seekable in
+///order to overwrite it once encoding is done.
+///
+///Encoder with number of channels known at compile time (see [Channels]) accepts only matching input.
+///
+///## Example
+///
+///```rust
+///use mp3lame_encoder::{Builder, Mp3Writer, DualPcm, FlushNoGap};
+///
+///let encoder = Builder::new().expect("Create LAME builder")
+///    .with_num_channels(2).expect("set channels")
+///    .with_sample_rate(44_100).expect("set sample rate")
+///    .with_vbr_mode(mp3lame_encoder::VbrMode::Mtrh).expect("set VBR")
+///    .build().expect("To initialize LAME encoder");
+///
+///let mut writer = Mp3Writer::new(encoder, std::io::Cursor::new(Vec::new())).expect("create writer");
+///writer.encode(DualPcm {
+///    left: &[0i16; 1152],
+///    right: &[0i16; 1152],
+///}).expect("To encode");
+///
+///let output = writer.finish::<FlushNoGap>().expect("to finish").into_inner();
+///assert!(!output.is_empty());
+///```
+pub struct Mp3Writer<W: Write + Seek, C = Dynamic> {
+    encoder: Encoder<C>,
+    writer: W,
+    buffer: Vec<u8>,
+    start: u64,
+    written: u64,
+}
+
+impl<W: Write + Seek, C: Channels> Mp3Writer<W, C> {
+    ///Creates new instance, writing MP3 starting at current position of `writer`.
+    pub fn new(encoder: Encoder<C>, mut writer: W) -> io::Result<Self> {
+        let start = writer.stream_position()?;
+
+        Ok(Self {
+            encoder,
+            writer,
+            buffer: Vec::new(),
+            start,
+            written: 0,
+        })
+    }
+
+    #[inline(always)]
+    ///Access underlying encoder
+    pub fn encoder(&self) -> &Encoder<C> {
+        &self.encoder
+    }
+
+    #[inline(always)]
+    ///Access underlying writer
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    #[inline(always)]
+    ///Access underlying writer mutably
+    ///
+    ///Changing position of writer will result in corrupted output.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.writer
+    }
+
+    #[inline(always)]
+    ///Returns number of bytes written so far
+    pub fn written(&self) -> u64 {
+        self.written
+    }
+
+    fn write_buffer(&mut self) -> io::Result<usize> {
+        self.writer.write_all(&self.buffer)?;
+        let written = self.buffer.len();
+        self.written = self.written.saturating_add(written as u64);
+        self.buffer.clear();
+        Ok(written)
+    }
+
+    ///Encodes PCM data, writing whatever available onto underlying writer
+    ///
+    ///Returns number of bytes written.
+    pub fn encode<I: EncoderInput>(&mut self, input: I) -> io::Result<usize> where C: Accepts<I> {
+        if let Err(error) = self.encoder.encode_into(input, &mut self.buffer) {
+            return Err(io::Error::new(io::ErrorKind::Other, error));
+        }
+        self.write_buffer()
+    }
+
+    ///Flushes encoder, writing LAME tag (if any) after [Id3Tag](crate::Id3Tag)
+    ///
+    ///Refer to [Encoder::flush] for flush types
+    ///
+    ///Returns underlying writer positioned at the end of MP3 data.
+    pub fn finish<T: EncoderFlush>(mut self) -> io::Result<W> {
+        if let Err(error) = self.encoder.flush_into::<T>(&mut self.buffer) {
+            return Err(io::Error::new(io::ErrorKind::Other, error));
+        }
+        self.write_buffer()?;
+
+        let lame_tag_size = self.encoder.lame_tag_size();
+        if lame_tag_size > 0 {
+            self.buffer.reserve(lame_tag_size);
+            if self.encoder.lame_tag_encode_to_vec(&mut self.buffer).is_some() {
+                let end = self.writer.stream_position()?;
+                let lame_tag_pos = self.start.saturating_add(self.encoder.id3v2_tag_size() as u64);
+
+                self.writer.seek(SeekFrom::Start(lame_tag_pos))?;
+                self.writer.write_all(&self.buffer)?;
+                self.writer.seek(SeekFrom::Start(end))?;
+            }
+        }
+
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
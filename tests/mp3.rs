@@ -167,3 +167,1037 @@ fn should_verify_vbr_tag_is_not_present_without_encode() {
     assert!(mp3_encoder.is_lame_tag_written());
     assert_eq!(mp3_encoder.lame_tag_size(), 0);
 }
+
+#[cfg(feature = "std")]
+#[test]
+fn should_write_lame_tag_after_id3_tag() {
+    use mp3lame_encoder::{Mp3Writer, DualPcm};
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Mtrh).expect("set VBR")
+        .with_id3_tag(Id3Tag {
+            title: b"Silence",
+            artist: &[],
+            album: b"Test",
+            album_art: ALBUM_ART,
+            year: b"2022",
+            comment: &[],
+        }).expect("Id3 tag")
+        .build().expect("To initialize LAME encoder");
+    let id3v2_tag_size = mp3_encoder.id3v2_tag_size();
+    assert!(id3v2_tag_size > ALBUM_ART.len());
+
+    let mut writer = Mp3Writer::new(mp3_encoder, io::Cursor::new(Vec::new())).expect("create writer");
+    let pcm = [0i16; 44_100];
+    for _ in 0..5 {
+        writer.encode(DualPcm {
+            left: &pcm,
+            right: &pcm,
+        }).expect("To encode");
+    }
+    assert!(writer.written() > id3v2_tag_size as u64);
+
+    let output = writer.finish::<FlushNoGap>().expect("to finish").into_inner();
+    let lame_frame = &output[id3v2_tag_size..id3v2_tag_size + 64];
+    assert_eq!(lame_frame[0], 0xFF);
+    assert!(lame_frame.windows(4).any(|tag| tag == b"Xing" || tag == b"Info"));
+}
+
+#[test]
+fn should_encode_into_vec_without_reserve() {
+    use mp3lame_encoder::DualPcm;
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_id3_tag(Id3Tag {
+            title: b"Silence",
+            artist: &[],
+            album: &[],
+            album_art: ALBUM_ART,
+            year: &[],
+            comment: &[],
+        }).expect("Id3 tag")
+        .build().expect("To initialize LAME encoder");
+
+    let mut mp3_out_buffer = Vec::new();
+    let pcm = [0i16; 4_410];
+    let input = DualPcm {
+        left: &pcm,
+        right: &pcm,
+    };
+    mp3_encoder.encode_into(input, &mut mp3_out_buffer).expect("To encode");
+    assert!(mp3_out_buffer.len() >= mp3_encoder.id3v2_tag_size());
+    mp3_encoder.encode_into(input, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+}
+
+#[cfg(feature = "decoder")]
+#[test]
+fn should_decode_encoded_mp3() {
+    use mp3lame_encoder::{Decoder, DualPcm, Mode, MAX_FRAME_SAMPLES};
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_brate(mp3lame_encoder::Bitrate::Kbps128).expect("set brate")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Off).expect("set VBR")
+        .with_mode(Mode::JointStereo).expect("set mode")
+        .build().expect("To initialize LAME encoder");
+
+    let pcm: Vec<i16> = (0..44_100).map(|idx| (((idx as f32) * 440.0 * 2.0 * core::f32::consts::PI / 44_100.0).sin() * 8_000.0) as i16).collect();
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(DualPcm {
+        left: &pcm,
+        right: &pcm,
+    }, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+
+    let mut decoder = Decoder::new().expect("create decoder");
+    assert!(decoder.info().is_none());
+    let mut left = [0i16; MAX_FRAME_SAMPLES];
+    let mut right = [0i16; MAX_FRAME_SAMPLES];
+    let mut decoded_samples = 0;
+
+    for chunk in mp3_out_buffer.chunks(1024) {
+        let mut input = chunk;
+        loop {
+            let samples_num = decoder.decode(input, &mut left, &mut right).expect("To decode");
+            input = &[];
+            if samples_num == 0 {
+                break;
+            }
+            decoded_samples += samples_num;
+        }
+    }
+
+    let info = decoder.info().expect("to have header");
+    assert_eq!(info.sample_rate, 44_100);
+    assert_eq!(info.num_channels, 2);
+    assert_eq!(info.bitrate, 128);
+    assert_eq!(info.mode, Mode::JointStereo);
+    assert_eq!(info.frame_size, 1152);
+    assert!(decoded_samples >= pcm.len());
+}
+
+#[test]
+fn should_write_id3_tag_builder() {
+    use mp3lame_encoder::{DualPcm, Id3TagBuilder, Id3TagError, Id3Version};
+
+    let tag = Id3TagBuilder::new().with_frame("TCO", "Composer");
+    let result = Builder::new().expect("Create LAME builder").with_id3_tag_builder(&tag).err();
+    assert_eq!(result, Some(Id3TagError::InvalidFrameId));
+
+    let tag = Id3TagBuilder::new().with_title("Колокол")
+                                  .with_artist("Artist")
+                                  .with_genre("Rock")
+                                  .with_track(300, Some(312))
+                                  .with_disc(1, Some(2))
+                                  .with_user_text("CATALOG", "ID-42")
+                                  .with_version(Id3Version::V2Only);
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_id3_tag_builder(&tag).expect("Id3 tag")
+        .build().expect("To initialize LAME encoder");
+    let id3v2_tag_size = mp3_encoder.id3v2_tag_size();
+    assert!(id3v2_tag_size > 0);
+
+    let mut mp3_out_buffer = Vec::new();
+    let pcm = [0i16; 1152];
+    mp3_encoder.encode_into(DualPcm {
+        left: &pcm,
+        right: &pcm,
+    }, &mut mp3_out_buffer).expect("To encode");
+    let id3v2_tag = &mp3_out_buffer[..id3v2_tag_size];
+    assert_eq!(&id3v2_tag[..3], b"ID3");
+    let title: Vec<u8> = "Колокол".encode_utf16().flat_map(|ch| ch.to_le_bytes()).collect();
+    assert!(id3v2_tag.windows(title.len()).any(|frame| frame == title));
+    //Track number outside of ID3v1 range is still written to ID3v2 tag
+    for frame in [&b"TIT2"[..], b"TPE1", b"TCON", b"TRCK", b"300/312", b"TPOS", b"TXXX", b"CATALOG", b"ID-42"] {
+        assert!(id3v2_tag.windows(frame.len()).any(|data| data == frame));
+    }
+}
+
+#[test]
+fn should_report_gain_analysis() {
+    use mp3lame_encoder::MonoPcm;
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder").build().expect("To initialize LAME encoder");
+    assert!(mp3_encoder.gain_analysis().is_none());
+
+    let builder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_find_replay_gain(true).expect("set replay gain");
+    #[cfg(feature = "decoder")]
+    let builder = builder.with_decode_on_the_fly(true).expect("set decode on the fly");
+    let mut mp3_encoder = builder.build().expect("To initialize LAME encoder");
+
+    let pcm: Vec<f32> = (0..44_100 * 2).map(|idx| ((idx as f32) * 1000.0 * 2.0 * core::f32::consts::PI / 44_100.0).sin() * 0.5).collect();
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+
+    let analysis = mp3_encoder.gain_analysis().expect("to have analysis");
+    let radio_gain_db = analysis.radio_gain_db.expect("to have radio gain");
+    assert!(radio_gain_db < 0.0, "loud sine should require attenuation, got {radio_gain_db}");
+
+    #[cfg(feature = "decoder")]
+    {
+        let peak_sample = analysis.peak_sample.expect("to have peak sample");
+        assert!(peak_sample > 10_000.0 && peak_sample < 32_767.0, "unexpected peak {peak_sample}");
+        assert!(analysis.noclip_gain_change_db.is_some());
+    }
+    #[cfg(not(feature = "decoder"))]
+    assert!(analysis.peak_sample.is_none());
+}
+
+#[cfg(feature = "tokio")]
+#[tokio::test]
+async fn should_encode_asynchronously() {
+    use core::pin::Pin;
+    use core::task::{Context, Poll};
+    use mp3lame_encoder::{AsyncMp3Writer, EncoderStream, InterleavedPcm};
+
+    struct Chunks(Vec<InterleavedPcm<'static, i16>>);
+
+    impl futures_core::Stream for Chunks {
+        type Item = InterleavedPcm<'static, i16>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.pop())
+        }
+    }
+
+    static PCM: [i16; 2 * 4_410] = [0; 2 * 4_410];
+
+    let build_encoder = || Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Mtrh).expect("set VBR")
+        .with_to_write_vbr_tag(true).expect("set to write VBR tag")
+        .build().expect("To initialize LAME encoder");
+
+    let mut writer = AsyncMp3Writer::new(build_encoder(), io::Cursor::new(Vec::new()));
+    for _ in 0..10 {
+        writer.encode(InterleavedPcm(&PCM[..])).await.expect("To encode");
+    }
+    let output = writer.finish_with_lame_tag::<FlushNoGap>().await.expect("to finish").into_inner();
+    assert!(output[..64].windows(4).any(|tag| tag == b"Xing" || tag == b"Info"));
+
+    let mut stream = EncoderStream::<_, FlushNoGap>::new(build_encoder(), Chunks(vec![InterleavedPcm(&PCM[..]); 10]));
+    let mut stream_output = Vec::new();
+    while let Some(frames) = std::future::poll_fn(|ctx| futures_core::Stream::poll_next(Pin::new(&mut stream), ctx)).await {
+        stream_output.extend_from_slice(&frames.expect("To encode"));
+    }
+    assert_eq!(output.len(), stream_output.len());
+}
+
+#[test]
+fn should_split_output_into_frames() {
+    use mp3lame_encoder::{DualPcm, FrameSplitter, Mp3Chunk, MpegVersion};
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Mtrh).expect("set VBR")
+        .with_id3_tag(Id3Tag {
+            title: b"Frames",
+            artist: &[],
+            album: &[],
+            album_art: &[],
+            year: &[],
+            comment: &[],
+        }).expect("Id3 tag")
+        .build().expect("To initialize LAME encoder");
+    let mut frames = FrameSplitter::new(&mp3_encoder);
+
+    let pcm: Vec<i16> = (0..44_100).map(|idx| (((idx as f32) * 440.0 * 2.0 * core::f32::consts::PI / 44_100.0).sin() * 8_000.0) as i16).collect();
+    let input = DualPcm {
+        left: &pcm,
+        right: &pcm,
+    };
+    mp3_encoder.encode_into(input, &mut frames).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut frames).expect("to flush");
+
+    let mut chunks = (0, 0, 0);
+    let mut total_len = 0;
+    let mut expected_position = 0;
+    while let Some(chunk) = frames.next_chunk() {
+        match chunk {
+            Mp3Chunk::Id3Tag(tag) => {
+                assert_eq!(tag.len(), mp3_encoder.id3v2_tag_size());
+                total_len += tag.len();
+                chunks.0 += 1;
+            },
+            Mp3Chunk::LameTag(tag) => {
+                assert_eq!(tag.len(), mp3_encoder.lame_tag_size());
+                total_len += tag.len();
+                chunks.1 += 1;
+            },
+            Mp3Chunk::Frame(frame) => {
+                assert_eq!(frame.header.version, MpegVersion::Mpeg1);
+                assert_eq!(frame.header.sample_rate, 44_100);
+                assert_eq!(frame.data.len(), frame.header.frame_len());
+                assert_eq!(frame.sample_position, expected_position);
+                expected_position += frame.header.samples_num() as u64;
+                total_len += frame.data.len();
+                chunks.2 += 1;
+            },
+            Mp3Chunk::Unknown(data) => panic!("Unexpected data of {} bytes", data.len()),
+        }
+    }
+
+    assert!(frames.pending().is_empty());
+    assert_eq!(chunks.0, 1);
+    assert_eq!(chunks.1, 1);
+    assert!(chunks.2 >= 44_100 / 1152);
+    assert_eq!(frames.sample_position(), expected_position);
+    assert!(total_len > mp3_encoder.id3v2_tag_size() + mp3_encoder.lame_tag_size());
+}
+
+#[test]
+fn should_build_with_presets() {
+    use mp3lame_encoder::{Bitrate, MonoPcm, Preset};
+
+    let pcm = [0i16; 4_410];
+    for preset in [Preset::V0, Preset::V2, Preset::V9, Preset::Medium, Preset::Standard, Preset::Extreme, Preset::Insane, Preset::Abr(Bitrate::Kbps160)] {
+        let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+            .with_num_channels(1).expect("set channels")
+            .with_preset(preset).expect("set preset")
+            .build().expect("To initialize LAME encoder");
+
+        let mut mp3_out_buffer = Vec::new();
+        mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+        mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+        assert!(!mp3_out_buffer.is_empty());
+    }
+}
+
+#[test]
+fn should_configure_abr_bitrate_bounds() {
+    use mp3lame_encoder::{Bitrate, MonoPcm, MpegVersion, VbrMode};
+
+    assert!(Bitrate::Kbps320.is_allowed_for(MpegVersion::Mpeg1));
+    assert!(!Bitrate::Kbps320.is_allowed_for(MpegVersion::Mpeg2));
+    assert!(!Bitrate::Kbps8.is_allowed_for(MpegVersion::Mpeg1));
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_vbr_mode(VbrMode::Abr).expect("set vbr mode")
+        .with_vbr_mean_bitrate(Bitrate::Kbps96).expect("set mean bitrate")
+        .with_vbr_min_bitrate(Bitrate::Kbps64).expect("set min bitrate")
+        .with_vbr_hard_min(true).expect("set hard min")
+        .build().expect("To initialize LAME encoder");
+
+    let pcm = [0i16; 4_410];
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    builder.set_vbr_max_bitrate(Bitrate::Kbps128).expect("set max bitrate");
+    assert!(builder.set_vbr_min_bitrate(Bitrate::Kbps160).is_err());
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    builder.set_output_sample_rate(core::num::NonZeroU32::new(22_050)).expect("set output sample rate");
+    assert!(builder.set_vbr_max_bitrate(Bitrate::Kbps192).is_err());
+    assert!(builder.set_vbr_mean_bitrate(Bitrate::Kbps192).is_err());
+    builder.set_vbr_min_bitrate(Bitrate::Kbps8).expect("set min bitrate");
+}
+
+#[test]
+fn should_validate_bitrate_for_output_sample_rate() {
+    use core::num::NonZeroU32;
+    use mp3lame_encoder::{Bitrate, BuildError, MpegVersion};
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    builder.set_output_sample_rate(NonZeroU32::new(22_050)).expect("set output sample rate");
+    builder.set_brate(Bitrate::Kbps320).expect("set brate");
+    let error = builder.validate().expect_err("320 kbps is not allowed for MPEG-2");
+    assert_eq!(error, BuildError::BitrateNotAllowed {
+        bitrate: 320,
+        version: MpegVersion::Mpeg2,
+        sample_rate: 22_050,
+    });
+    assert_eq!(error.to_string(), "bitrate 320 not allowed for MPEG-2 22050 Hz");
+
+    builder.set_brate(Bitrate::Kbps144).expect("set brate");
+    builder.validate().expect("144 kbps is allowed for MPEG-2");
+    builder.build().expect("To initialize LAME encoder");
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    builder.set_output_sample_rate(NonZeroU32::new(44_100)).expect("set output sample rate");
+    builder.set_brate(Bitrate::Kbps144).expect("set brate");
+    assert!(builder.build().is_err());
+}
+
+#[test]
+fn should_configure_filters() {
+    use core::num::NonZeroU32;
+    use mp3lame_encoder::{BuildError, Filter};
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_lowpass(Some(Filter::Frequency(NonZeroU32::new(16_000).unwrap()))).expect("set lowpass")
+        .with_lowpass_width(NonZeroU32::new(1_000)).expect("set lowpass width")
+        .with_highpass(Some(Filter::Frequency(NonZeroU32::new(100).unwrap()))).expect("set highpass")
+        .build().expect("To initialize LAME encoder");
+    let lowpass = mp3_encoder.lowpass_freq().expect("lowpass to be enabled");
+    assert!(lowpass <= 16_000);
+    assert!(mp3_encoder.highpass_freq().is_some());
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_lowpass(Some(Filter::Disabled)).expect("set lowpass")
+        .build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.lowpass_freq(), None);
+    assert_eq!(mp3_encoder.highpass_freq(), None);
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    let error = builder.set_lowpass(Some(Filter::Frequency(NonZeroU32::new(22_050).unwrap()))).expect_err("Must be below Nyquist");
+    assert_eq!(error, BuildError::FilterAboveNyquist { frequency: 22_050, sample_rate: 44_100 });
+
+    builder.set_lowpass(Some(Filter::Frequency(NonZeroU32::new(15_000).unwrap()))).expect("set lowpass");
+    builder.set_output_sample_rate(NonZeroU32::new(16_000)).expect("set output sample rate");
+    assert!(builder.validate().is_err());
+}
+
+#[cfg(feature = "symphonia")]
+#[test]
+fn should_encode_symphonia_buffers() {
+    use mp3lame_encoder::EncodeError;
+    use symphonia::core::audio::{AudioBuffer, Channels, Signal, SignalSpec};
+
+    let file = fs::File::open("tests/Bell3.ogg").expect("open FILE");
+    let file = MediaSourceStream::new(Box::new(file), Default::default());
+    let mut hint = Hint::new();
+    hint.with_extension("ogg");
+
+    let probed = symphonia::default::get_probe().format(&hint, file, &Default::default(), &Default::default()).expect("To probe ogg file");
+    let mut format = probed.format;
+    let track = format.default_track().expect("Get default track");
+    let track_id = track.id;
+    let mut decoder = symphonia::default::get_codecs().make(&track.codec_params, &Default::default()).unwrap();
+
+    let mut mp3_encoder = None;
+    let mut mp3_out_buffer = Vec::new();
+    loop {
+        let packet = match format.next_packet() {
+            Ok(packet) => packet,
+            Err(SymError::IoError(error)) if error.kind() == io::ErrorKind::UnexpectedEof => break,
+            Err(error) => panic!("Unexpected error reading packets: {error}"),
+        };
+        if packet.track_id() != track_id {
+            continue
+        }
+
+        let audio_buf = decoder.decode(&packet).expect("To decode packet");
+        let mp3_encoder = mp3_encoder.get_or_insert_with(|| {
+            let spec = audio_buf.spec();
+            Builder::new().expect("Create LAME builder")
+                .with_num_channels(spec.channels.count() as u8).expect("set channels")
+                .with_sample_rate(spec.rate).expect("set sample rate")
+                .build().expect("To initialize LAME encoder")
+        });
+        mp3_encoder.encode_into(&audio_buf, &mut mp3_out_buffer).expect("To encode");
+    }
+
+    let mut mp3_encoder = mp3_encoder.expect("To decode at least one packet");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+
+    let spec = SignalSpec::new(44_100, Channels::FRONT_LEFT | Channels::FRONT_RIGHT | Channels::FRONT_CENTRE);
+    let mut audio_buf = AudioBuffer::<i16>::new(1152, spec);
+    audio_buf.render_silence(None);
+    let result = mp3_encoder.encode_into(&audio_buf, &mut mp3_out_buffer);
+    assert_eq!(result, Err(EncodeError::UnsupportedChannels));
+}
+
+#[cfg(feature = "std")]
+fn make_wav(format_tag: u16, num_channels: u16, sample_rate: u32, bits_per_sample: u16, data: &[u8]) -> Vec<u8> {
+    let block_align = num_channels * bits_per_sample / 8;
+    let mut fmt = Vec::new();
+    fmt.extend_from_slice(&format_tag.to_le_bytes());
+    fmt.extend_from_slice(&num_channels.to_le_bytes());
+    fmt.extend_from_slice(&sample_rate.to_le_bytes());
+    fmt.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+    fmt.extend_from_slice(&block_align.to_le_bytes());
+    fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+    if format_tag == 0xFFFE {
+        fmt.extend_from_slice(&22u16.to_le_bytes());
+        fmt.extend_from_slice(&bits_per_sample.to_le_bytes());
+        fmt.extend_from_slice(&0u32.to_le_bytes());
+        //KSDATAFORMAT_SUBTYPE_PCM
+        fmt.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0x10, 0, 0x80, 0, 0, 0xAA, 0, 0x38, 0x9B, 0x71]);
+    }
+
+    let mut wav = Vec::new();
+    wav.extend_from_slice(b"RIFF");
+    wav.extend_from_slice(&((4 + 8 + fmt.len() + 8 + 6 + 8 + data.len()) as u32).to_le_bytes());
+    wav.extend_from_slice(b"WAVE");
+    wav.extend_from_slice(b"fmt ");
+    wav.extend_from_slice(&(fmt.len() as u32).to_le_bytes());
+    wav.extend_from_slice(&fmt);
+    //Unknown chunks must be skipped
+    wav.extend_from_slice(b"LIST");
+    wav.extend_from_slice(&6u32.to_le_bytes());
+    wav.extend_from_slice(&[0; 6]);
+    wav.extend_from_slice(b"data");
+    wav.extend_from_slice(&(data.len() as u32).to_le_bytes());
+    wav.extend_from_slice(data);
+    wav
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_transcode_wav_to_mp3() {
+    use mp3lame_encoder::{wav_to_mp3, WavHeader, WavSampleFormat};
+
+    let wav = make_wav(1, 2, 44_100, 16, &[0u8; 44_100 * 2 * 2]);
+    let header = WavHeader::read(&mut &wav[..]).expect("To read WAV header");
+    assert_eq!(header, WavHeader {
+        sample_rate: 44_100,
+        num_channels: 2,
+        sample_format: WavSampleFormat::I16,
+        data_len: Some(44_100 * 2 * 2),
+    });
+
+    let output = wav_to_mp3(&wav[..], Builder::new().expect("Create LAME builder"), io::Cursor::new(Vec::new())).expect("To transcode").into_inner();
+    assert!(!output.is_empty());
+
+    let wav = make_wav(0xFFFE, 1, 22_050, 24, &[0u8; 22_050 * 3]);
+    let header = WavHeader::read(&mut &wav[..]).expect("To read WAV header");
+    assert_eq!(header.sample_format, WavSampleFormat::I24);
+    assert_eq!(header.num_channels, 1);
+    let output = wav_to_mp3(&wav[..], Builder::new().expect("Create LAME builder"), io::Cursor::new(Vec::new())).expect("To transcode").into_inner();
+    assert!(!output.is_empty());
+
+    let wav = make_wav(3, 2, 48_000, 32, &[0u8; 4_800 * 2 * 4]);
+    let output = wav_to_mp3(&wav[..], Builder::new().expect("Create LAME builder"), io::Cursor::new(Vec::new())).expect("To transcode").into_inner();
+    assert!(!output.is_empty());
+
+    //Empty data chunk followed by other chunk
+    let mut wav = make_wav(1, 2, 44_100, 16, &[]);
+    wav.extend_from_slice(b"LIST\x04\0\0\0INFO");
+    let header = WavHeader::read(&mut &wav[..]).expect("To read WAV header");
+    assert_eq!(header.data_len, Some(0));
+    let output = wav_to_mp3(&wav[..], Builder::new().expect("Create LAME builder"), io::Cursor::new(Vec::new())).expect("To transcode").into_inner();
+    let empty_output = wav_to_mp3(&make_wav(1, 2, 44_100, 16, &[])[..], Builder::new().expect("Create LAME builder"), io::Cursor::new(Vec::new())).expect("To transcode").into_inner();
+    assert_eq!(output, empty_output);
+
+    let wav = make_wav(1, 6, 48_000, 16, &[0u8; 4_800 * 6 * 2]);
+    let error = WavHeader::read(&mut &wav[..]).expect_err("Only mono and stereo are supported");
+    assert_eq!(error.kind(), io::ErrorKind::InvalidData);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn should_encode_wav_using_cli() {
+    let dir = std::env::temp_dir();
+    let input = dir.join("mp3lame-rs-cli-test.wav");
+    let output = dir.join("mp3lame-rs-cli-test.mp3");
+    fs::write(&input, make_wav(1, 2, 44_100, 16, &[0u8; 44_100 * 2 * 2])).expect("write WAV");
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_mp3lame-rs"))
+        .args(["-b", "128", "-q", "2", "-m", "j", "--tt", "Title", "--ta", "Artist", "--ti", "tests/album_art.jpg", "--nogap"])
+        .arg(&input)
+        .arg(&output)
+        .status()
+        .expect("run mp3lame-rs");
+    assert!(status.success());
+
+    let mp3 = fs::read(&output).expect("read MP3");
+    assert!(mp3.starts_with(b"ID3"));
+    assert!(mp3.len() > ALBUM_ART.len());
+
+    let status = std::process::Command::new(env!("CARGO_BIN_EXE_mp3lame-rs"))
+        .args(["-b", "100"])
+        .arg(&input)
+        .arg(&output)
+        .status()
+        .expect("run mp3lame-rs");
+    assert!(!status.success());
+
+    let _ = fs::remove_file(input);
+    let _ = fs::remove_file(output);
+}
+
+#[test]
+fn should_encode_8bit_and_packed_24bit_pcm() {
+    use mp3lame_encoder::{DualPcm, DualPcm24, InterleavedPcm, InterleavedPcm24, MonoPcm24};
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .build().expect("To initialize LAME encoder");
+
+    //Larger than single conversion chunk
+    let pcm_u8 = [128u8; 4_410];
+    let pcm_i8 = [0i8; 4_410];
+    let pcm_24 = [0u8; 4_410 * 3];
+
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(DualPcm { left: &pcm_u8[..], right: &pcm_u8[..] }, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(InterleavedPcm(&pcm_i8[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(DualPcm24 { left: &pcm_24[..], right: &pcm_24[..] }, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(InterleavedPcm24(&pcm_24[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .build().expect("To initialize LAME encoder");
+
+    //Buffer must fit worst case before anything is encoded
+    let mut small_buffer = [core::mem::MaybeUninit::uninit(); 4_410];
+    assert_eq!(mp3_encoder.encode(MonoPcm(&pcm_u8[..]), &mut small_buffer), Err(mp3lame_encoder::EncodeError::BufferTooSmall));
+
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(MonoPcm(&pcm_u8[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(MonoPcm(&pcm_i8[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(MonoPcm24(&pcm_24[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+}
+
+#[test]
+fn should_encode_raw_pcm() {
+    use mp3lame_encoder::{EncodeError, Endian, Layout, RawPcm, SampleFormat};
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .build().expect("To initialize LAME encoder");
+
+    //Offset by one byte to make it misaligned
+    let mut bytes = vec![0u8; 4_410 * 2 * 8 + 1];
+    for (idx, sample) in bytes[1..].chunks_exact_mut(2).enumerate() {
+        sample.copy_from_slice(&((idx as i16).wrapping_mul(64)).to_be_bytes());
+    }
+    let bytes = &bytes[1..];
+
+    let mut mp3_out_buffer = Vec::new();
+    for (format, endian, layout) in [
+        (SampleFormat::S16, Endian::Big, Layout::Interleaved),
+        (SampleFormat::S16, Endian::Little, Layout::Planar),
+        (SampleFormat::U8, Endian::Little, Layout::Interleaved),
+        (SampleFormat::S24, Endian::Big, Layout::Planar),
+        (SampleFormat::S32, Endian::Little, Layout::Interleaved),
+    ] {
+        let input = RawPcm {
+            bytes: &bytes[..4_410 * 2 * format.sample_size()],
+            format,
+            endian,
+            layout,
+            channels: 2,
+        };
+        mp3_encoder.encode_into(input, &mut mp3_out_buffer).expect("To encode");
+    }
+    mp3_encoder.flush_into::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+
+    let input = RawPcm {
+        bytes,
+        format: SampleFormat::F64,
+        endian: Endian::Little,
+        layout: Layout::Planar,
+        channels: 1,
+    };
+    assert_eq!(mp3_encoder.encode_into(input, &mut mp3_out_buffer), Err(EncodeError::ChannelMismatch));
+}
+
+#[test]
+fn should_validate_input_channels() {
+    use mp3lame_encoder::{DualPcm, EncodeError, InterleavedPcm};
+
+    let pcm = [0i16; 1152];
+    let mut mp3_out_buffer = Vec::new();
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer), Err(EncodeError::ChannelMismatch));
+    assert_eq!(mp3_encoder.encode_into(DualPcm { left: &pcm[..], right: &pcm[1..] }, &mut mp3_out_buffer), Err(EncodeError::UnequalChannelLengths));
+    assert_eq!(mp3_encoder.encode_into(InterleavedPcm(&pcm[1..]), &mut mp3_out_buffer), Err(EncodeError::OddInterleavedLength));
+    assert!(mp3_out_buffer.is_empty());
+    mp3_encoder.encode_into(InterleavedPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.encode_into(DualPcm { left: &pcm[..], right: &pcm[..] }, &mut mp3_out_buffer), Err(EncodeError::ChannelMismatch));
+    assert_eq!(mp3_encoder.encode_into(InterleavedPcm(&pcm[..]), &mut mp3_out_buffer), Err(EncodeError::ChannelMismatch));
+    mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+}
+
+#[test]
+fn should_encode_with_typed_encoder() {
+    use mp3lame_encoder::{DualPcm, InterleavedPcm, Mono, Stereo};
+
+    let pcm = [0i16; 1152 * 2];
+    let mut mp3_out_buffer = Vec::new();
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .build_typed::<Mono>().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.num_channels(), 1);
+    mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_to_vec::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .build_typed::<Stereo>().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.num_channels(), 2);
+    mp3_encoder.encode_into(DualPcm { left: &pcm[..], right: &pcm[..] }, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.encode_into(InterleavedPcm(&pcm[..]), &mut mp3_out_buffer).expect("To encode");
+
+    let mut mp3_encoder = mp3_encoder.into_dynamic();
+    assert_eq!(mp3_encoder.encode_into(MonoPcm(&pcm[..]), &mut mp3_out_buffer), Err(mp3lame_encoder::EncodeError::ChannelMismatch));
+
+    #[cfg(feature = "std")]
+    {
+        let mp3_encoder = Builder::new().expect("Create LAME builder")
+            .with_num_channels(2).expect("set channels")
+            .build_typed::<Stereo>().expect("To initialize LAME encoder");
+        let mut writer = mp3lame_encoder::Mp3Writer::new(mp3_encoder, io::Cursor::new(Vec::new())).expect("create writer");
+        writer.encode(DualPcm { left: &pcm[..], right: &pcm[..] }).expect("To encode");
+        assert!(!writer.finish::<FlushNoGap>().expect("to finish").into_inner().is_empty());
+    }
+}
+
+#[test]
+fn should_configure_scale() {
+    use mp3lame_encoder::{BuildError, Gain};
+
+    assert_eq!(Gain::Linear(0.5).to_linear(), Some(0.5));
+    assert_eq!(Gain::Db(0.0).to_linear(), Some(1.0));
+    assert!((Gain::Db(-6.0).to_linear().unwrap() - 0.501_187).abs() < 1e-5);
+    assert!((Gain::Db(20.0).to_linear().unwrap() - 10.0).abs() < 1e-4);
+
+    let mut builder = Builder::new().expect("Create LAME builder");
+    assert_eq!(builder.set_scale(Gain::Linear(-1.0)), Err(BuildError::InvalidGain));
+    assert_eq!(builder.set_scale(Gain::Db(f32::NAN)), Err(BuildError::InvalidGain));
+    assert_eq!(builder.set_channel_scale(Gain::Linear(1.0), Gain::Linear(f32::INFINITY)), Err(BuildError::InvalidGain));
+
+    let mp3_encoder = builder.with_scale(Gain::Db(-6.0)).expect("set scale")
+        .with_channel_scale(Gain::Linear(0.5), Gain::Linear(2.0)).expect("set channel scale")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Mtrh).expect("set VBR")
+        .build().expect("To initialize LAME encoder");
+    assert!((mp3_encoder.scale() - 0.501_187).abs() < 1e-5);
+    assert_eq!(mp3_encoder.channel_scale(), (0.5, 2.0));
+
+    //CBR compensates for clipping by lowering gain
+    let mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_scale(Gain::Db(-6.0)).expect("set scale")
+        .with_vbr_mode(mp3lame_encoder::VbrMode::Off).expect("set CBR")
+        .build().expect("To initialize LAME encoder");
+    assert!(mp3_encoder.scale() > 0.0 && mp3_encoder.scale() < 0.501_187);
+}
+
+#[test]
+fn should_configure_header_flags() {
+    use mp3lame_encoder::{DualPcm, Emphasis, FrameSplitter, Mp3Chunk};
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder").build().expect("To initialize LAME encoder");
+    assert!(!mp3_encoder.is_copyright());
+    assert!(mp3_encoder.is_original());
+    assert!(!mp3_encoder.is_extension());
+    assert!(!mp3_encoder.is_crc());
+    assert_eq!(mp3_encoder.emphasis(), Emphasis::None);
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_copyright(true).expect("set copyright")
+        .with_original(false).expect("set original")
+        .with_extension(true).expect("set extension")
+        .with_crc(true).expect("set crc")
+        .with_emphasis(Emphasis::CcittJ17).expect("set emphasis")
+        .build().expect("To initialize LAME encoder");
+    assert!(mp3_encoder.is_copyright());
+    assert!(!mp3_encoder.is_original());
+    assert!(mp3_encoder.is_extension());
+    assert!(mp3_encoder.is_crc());
+    assert_eq!(mp3_encoder.emphasis(), Emphasis::CcittJ17);
+
+    let mut frames = FrameSplitter::new(&mp3_encoder);
+    let pcm = [0i16; 1152 * 4];
+    mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut frames).expect("To encode");
+    mp3_encoder.flush_into::<FlushNoGap>(&mut frames).expect("To flush");
+
+    let mut frames_num = 0;
+    while let Some(chunk) = frames.next_chunk() {
+        if let Mp3Chunk::Frame(frame) = chunk {
+            frames_num += 1;
+            assert!(frame.header.crc);
+            //private bit, then copyright, original and emphasis bits
+            assert_eq!(frame.data[2] & 1, 1);
+            assert_eq!(frame.data[3] & 0b1111, 0b1011);
+        }
+    }
+    assert_ne!(frames_num, 0);
+}
+
+#[test]
+fn should_configure_free_format_and_iso_compliance() {
+    use core::num::NonZeroU32;
+    use mp3lame_encoder::{Bitrate, BuildError, DualPcm, VbrMode};
+
+    let mut builder = Builder::new().expect("Create LAME builder")
+        .with_free_format(true).expect("set free format")
+        .with_vbr_mode(VbrMode::Mtrh).expect("set VBR");
+    assert_eq!(builder.validate(), Err(BuildError::IncompatibleOptions { option: "free format", conflict: "VBR" }));
+    builder.set_vbr_mode(VbrMode::Off).expect("set CBR");
+    //Not allowed for MPEG-1, unless free format is used
+    builder.set_output_sample_rate(NonZeroU32::new(44_100)).expect("set output sample rate");
+    builder.set_brate(Bitrate::Kbps144).expect("set brate");
+    let mp3_encoder = builder.build().expect("To initialize LAME encoder");
+    assert!(mp3_encoder.is_free_format());
+    //LAME's default maximum frame buffer is not strict ISO
+    assert!(!mp3_encoder.is_strict_iso());
+    assert!(!mp3_encoder.is_reservoir_disabled());
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_strict_iso(true).expect("set strict ISO")
+        .with_disable_reservoir(true).expect("disable reservoir")
+        .build().expect("To initialize LAME encoder");
+    assert!(!mp3_encoder.is_free_format());
+    assert!(mp3_encoder.is_strict_iso());
+    assert!(mp3_encoder.is_reservoir_disabled());
+
+    let disabled_encoder = Builder::new().expect("Create LAME builder")
+        .with_strict_iso(true).expect("set strict ISO")
+        .with_strict_iso(false).expect("unset strict ISO")
+        .build().expect("To initialize LAME encoder");
+    assert!(!disabled_encoder.is_strict_iso());
+
+    let pcm = [0i16; 1152 * 4];
+    let mut mp3_out_buffer = Vec::new();
+    mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut mp3_out_buffer).expect("To encode");
+    mp3_encoder.flush_to_vec::<FlushNoGap>(&mut mp3_out_buffer).expect("to flush");
+    assert!(!mp3_out_buffer.is_empty());
+}
+
+#[test]
+fn should_report_splice_points() {
+    use mp3lame_encoder::{DualPcm, FrameSplitter, Mp3Chunk, Mp3Frame};
+
+    let pcm: Vec<i16> = (0..44_100).map(|idx| (((idx as f32) * 440.0 * 2.0 * core::f32::consts::PI / 44_100.0).sin() * 8_000.0) as i16).collect();
+    for disable_reservoir in [false, true] {
+        let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+            .with_num_channels(2).expect("set channels")
+            .with_sample_rate(44_100).expect("set sample rate")
+            .with_disable_reservoir(disable_reservoir).expect("set reservoir")
+            .build().expect("To initialize LAME encoder");
+        let mut frames = FrameSplitter::new(&mp3_encoder);
+        mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut frames).expect("To encode");
+        mp3_encoder.flush_into::<FlushNoGap>(&mut frames).expect("to flush");
+
+        let mut expected_position = 0;
+        let mut splice_points = 0;
+        let mut frames_num = 0;
+        while let Some(chunk) = frames.next_chunk() {
+            match chunk {
+                Mp3Chunk::Frame(frame) => {
+                    assert_eq!(frame.byte_position, expected_position);
+                    if frames_num == 0 || disable_reservoir {
+                        assert!(frame.is_splice_point());
+                    }
+                    splice_points += frame.is_splice_point() as usize;
+                    assert_eq!(Mp3Frame { data: &frame.data[..5], ..frame }.main_data_begin(), None);
+                    frames_num += 1;
+                    expected_position += frame.data.len() as u64;
+                },
+                Mp3Chunk::Id3Tag(data) | Mp3Chunk::LameTag(data) | Mp3Chunk::Unknown(data) => expected_position += data.len() as u64,
+            }
+        }
+        assert_eq!(frames.byte_position(), expected_position);
+        if disable_reservoir {
+            assert_eq!(splice_points, frames_num);
+        } else {
+            assert!(splice_points < frames_num);
+        }
+    }
+}
+
+#[test]
+fn should_report_encoder_config() {
+    use core::num::NonZeroU32;
+    use mp3lame_encoder::{Bitrate, Mode, MpegVersion, Quality, VbrMode};
+
+    let mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_output_sample_rate(NonZeroU32::new(22_050)).expect("set output sample rate")
+        .with_brate(Bitrate::Kbps64).expect("set brate")
+        .with_mode(Mode::JointStereo).expect("set mode")
+        .with_disable_reservoir(true).expect("disable reservoir")
+        .build().expect("To initialize LAME encoder");
+    let config = mp3_encoder.config();
+    assert_eq!(config.sample_rate, 44_100);
+    assert_eq!(config.output_sample_rate, 22_050);
+    assert_eq!(config.num_channels, 2);
+    assert_eq!(config.version, MpegVersion::Mpeg2);
+    assert_eq!(config.mode, Mode::JointStereo);
+    assert_eq!(config.vbr_mode, VbrMode::Off);
+    assert_eq!(config.bitrate, Some(Bitrate::Kbps64));
+    assert_eq!(config.vbr_quality, None);
+    assert_eq!(config.frame_size, 576);
+    assert_eq!(config.encoder_delay, 576);
+    assert_eq!(config.lowpass_freq, mp3_encoder.lowpass_freq());
+    assert!(config.disable_reservoir);
+    assert!(!config.free_format);
+
+    let config = Builder::new().expect("Create LAME builder")
+        .with_vbr_mode(VbrMode::Abr).expect("set VBR")
+        .with_vbr_mean_bitrate(Bitrate::Kbps128).expect("set mean bitrate")
+        .build().expect("To initialize LAME encoder")
+        .config();
+    assert_eq!(config.version, MpegVersion::Mpeg1);
+    assert_eq!(config.frame_size, 1152);
+    assert_eq!(config.vbr_mode, VbrMode::Abr);
+    assert_eq!(config.bitrate, None);
+    assert_eq!(config.vbr_mean_bitrate, Some(128));
+
+    let config = Builder::new().expect("Create LAME builder")
+        .with_vbr_mode(VbrMode::Mtrh).expect("set VBR")
+        .with_vbr_quality(Quality::Good).expect("set VBR quality")
+        .build().expect("To initialize LAME encoder")
+        .config();
+    assert_eq!(config.vbr_quality, Some(Quality::Good));
+}
+
+#[test]
+fn should_build_encoders_from_settings() {
+    use mp3lame_encoder::{Bitrate, BuildError, DualPcm, EncoderSettings, Id3TagError, Id3TagSettings, Mode, Quality, Stereo};
+
+    let settings = EncoderSettings {
+        num_channels: Some(2),
+        sample_rate: Some(44_100),
+        brate: Some(Bitrate::Kbps160),
+        quality: Some(Quality::NearBest),
+        mode: Some(Mode::JointStereo),
+        crc: Some(true),
+        id3_tag: Some(Id3TagSettings {
+            title: Some("Settings".to_owned()),
+            track: Some((1, Some(2))),
+            ..Id3TagSettings::default()
+        }),
+        ..EncoderSettings::default()
+    };
+
+    let pcm = [0i16; 1152];
+    for _ in 0..2 {
+        let mut mp3_encoder = settings.build_typed::<Stereo>().expect("To initialize LAME encoder");
+        let config = mp3_encoder.config();
+        assert_eq!(config.bitrate, Some(Bitrate::Kbps160));
+        assert_eq!(config.mode, Mode::JointStereo);
+        assert!(mp3_encoder.is_crc());
+        assert_ne!(mp3_encoder.id3v2_tag_size(), 0);
+
+        let mut mp3_out_buffer = Vec::new();
+        mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut mp3_out_buffer).expect("To encode");
+        assert!(mp3_out_buffer.starts_with(b"ID3"));
+    }
+
+    let builder = settings.builder().expect("To configure builder");
+    assert_eq!(builder.build().expect("To initialize LAME encoder").num_channels(), 2);
+
+    let settings = EncoderSettings {
+        id3_tag: Some(Id3TagSettings {
+            frames: vec![("bad".to_owned(), "value".to_owned())],
+            ..Id3TagSettings::default()
+        }),
+        ..EncoderSettings::default()
+    };
+    assert_eq!(settings.build().err(), Some(BuildError::Id3Tag(Id3TagError::InvalidFrameId)));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn should_deserialize_encoder_settings() {
+    use mp3lame_encoder::{Bitrate, EncoderSettings, Filter, Gain, VbrMode};
+
+    let settings: EncoderSettings = serde_json::from_str(r#"{
+        "sample_rate": 48000,
+        "vbr_mode": "Abr",
+        "vbr_mean_bitrate": "Kbps192",
+        "lowpass": { "Frequency": 18000 },
+        "scale": { "Db": -3.0 },
+        "id3_tag": { "artist": "Artist" }
+    }"#).expect("To parse settings");
+    assert_eq!(settings.sample_rate, Some(48_000));
+    assert_eq!(settings.vbr_mode, Some(VbrMode::Abr));
+    assert_eq!(settings.vbr_mean_bitrate, Some(Bitrate::Kbps192));
+    assert_eq!(settings.lowpass, Some(Filter::Frequency(core::num::NonZeroU32::new(18_000).unwrap())));
+    assert_eq!(settings.scale, Some(Gain::Db(-3.0)));
+    assert_eq!(settings.id3_tag.as_ref().and_then(|tag| tag.artist.as_deref()), Some("Artist"));
+    assert_eq!(settings.num_channels, None);
+
+    let json = serde_json::to_string(&settings).expect("To serialize settings");
+    let parsed: EncoderSettings = serde_json::from_str(&json).expect("To parse settings");
+    assert_eq!(parsed, settings);
+
+    let mp3_encoder = settings.build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.config().vbr_mean_bitrate, Some(192));
+    assert_eq!(mp3_encoder.lowpass_freq(), Some(18_000));
+}
+
+#[test]
+fn should_report_encode_stats() {
+    use mp3lame_encoder::{DualPcm, FlushGap, FrameSplitter, Mode, Mp3Chunk, VbrMode};
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .with_sample_rate(44_100).expect("set sample rate")
+        .with_vbr_mode(VbrMode::Mtrh).expect("set VBR")
+        .build().expect("To initialize LAME encoder");
+
+    let stats = mp3_encoder.stats();
+    assert_eq!(stats.frames, 0);
+    assert_eq!(stats.average_bitrate, 0.0);
+    assert_eq!(stats.duration, core::time::Duration::ZERO);
+
+    let pcm: Vec<i16> = (0..44_100).map(|idx| (((idx as f32) * 440.0 * 2.0 * core::f32::consts::PI / 44_100.0).sin() * 8_000.0) as i16).collect();
+    let mut frames = FrameSplitter::new(&mp3_encoder);
+    mp3_encoder.encode_into(DualPcm { left: &pcm, right: &pcm }, &mut frames).expect("To encode");
+    let partial_frames = mp3_encoder.stats().frames;
+    assert_ne!(partial_frames, 0);
+
+    //Padding remaining samples with silence encodes them into final frames
+    mp3_encoder.flush_into::<FlushGap>(&mut frames).expect("to flush");
+    let mut frames_num = 0;
+    while let Some(chunk) = frames.next_chunk() {
+        frames_num += matches!(chunk, Mp3Chunk::Frame(_)) as u32;
+    }
+    let stats = mp3_encoder.stats();
+    assert!(stats.frames > partial_frames);
+    assert_eq!(stats.frames, frames_num);
+    assert_eq!(stats.bitrates.len(), 14);
+    assert_eq!(stats.bitrates.iter().map(|(_, frames)| *frames).sum::<u32>(), stats.frames);
+    assert_eq!(stats.modes.iter().map(|(_, frames)| *frames).sum::<u32>(), stats.frames);
+    assert!(stats.modes.iter().all(|(mode, _)| matches!(mode, Mode::Stereo | Mode::JointStereo)));
+    assert_ne!(stats.block_types.long, 0);
+    assert!(stats.average_bitrate >= 32.0 && stats.average_bitrate <= 320.0);
+    assert!(stats.duration >= core::time::Duration::from_secs(1));
+}
+
+#[test]
+fn should_reject_incomplete_24bit_samples() {
+    use mp3lame_encoder::{DualPcm24, EncodeError, InterleavedPcm24, MonoPcm24};
+
+    let pcm_24 = [0u8; 1152 * 6];
+    let mut mp3_out_buffer = Vec::new();
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(1).expect("set channels")
+        .build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.encode_into(MonoPcm24(&pcm_24[1..]), &mut mp3_out_buffer), Err(EncodeError::IncompleteSample));
+    mp3_encoder.encode_into(MonoPcm24(&pcm_24[3..]), &mut mp3_out_buffer).expect("To encode");
+
+    let mut mp3_encoder = Builder::new().expect("Create LAME builder")
+        .with_num_channels(2).expect("set channels")
+        .build().expect("To initialize LAME encoder");
+    assert_eq!(mp3_encoder.encode_into(DualPcm24 { left: &pcm_24[2..], right: &pcm_24[2..] }, &mut mp3_out_buffer), Err(EncodeError::IncompleteSample));
+    assert_eq!(mp3_encoder.encode_into(InterleavedPcm24(&pcm_24[3..]), &mut mp3_out_buffer), Err(EncodeError::OddInterleavedLength));
+    mp3_encoder.encode_into(DualPcm24 { left: &pcm_24[3..], right: &pcm_24[3..] }, &mut mp3_out_buffer).expect("To encode");
+}